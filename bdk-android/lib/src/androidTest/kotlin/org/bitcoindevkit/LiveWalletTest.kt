@@ -5,6 +5,7 @@ import androidx.test.ext.junit.runners.AndroidJUnit4
 import androidx.test.platform.app.InstrumentationRegistry
 import org.junit.runner.RunWith
 import kotlin.test.AfterTest
+import kotlin.test.assertEquals
 import kotlin.test.assertTrue
 import java.io.File
 import org.rustbitcoin.bitcoin.Network
@@ -91,6 +92,7 @@ class LiveWalletTest {
         val feeRate: FeeRate = wallet.calculateFeeRate(tx)
         println("Tx fee rate is: ${feeRate.toSatPerVbCeil()} sat/vB")
 
-        esploraClient.broadcast(tx)
+        val txid: String = esploraClient.broadcast(tx)
+        assertEquals(tx.computeTxid(), txid, "Broadcast txid should match the transaction txid")
     }
 }
@@ -1,24 +1,48 @@
 use crate::error::PsbtFinalizeError;
 use crate::error::{
-    AddressParseError, FromScriptError, PsbtError, PsbtParseError, TransactionError,
+    AddressParseError, FromScriptError, MessageSignatureError, PaymentUriParseError, PsbtError,
+    PsbtParseError, TransactionError,
 };
 
+use bitcoin_ffi::Amount;
+use bitcoin_ffi::FeeRate;
 use bitcoin_ffi::OutPoint;
 use bitcoin_ffi::Script;
 
+use bdk_wallet::bitcoin::absolute::LockTime;
+use bdk_wallet::bitcoin::address::AddressType as BdkAddressType;
 use bdk_wallet::bitcoin::address::{NetworkChecked, NetworkUnchecked};
-use bdk_wallet::bitcoin::consensus::encode::serialize;
+use bdk_wallet::bitcoin::amount::Denomination;
+use bdk_wallet::bitcoin::base64::engine::general_purpose::STANDARD;
+use bdk_wallet::bitcoin::base64::Engine;
+use bdk_wallet::bitcoin::consensus::encode::{deserialize, serialize};
 use bdk_wallet::bitcoin::consensus::Decodable;
+use bdk_wallet::bitcoin::ecdsa::Signature as EcdsaSignature;
+use bdk_wallet::bitcoin::hashes::{sha256, Hash, HashEngine};
+use bdk_wallet::bitcoin::hex::FromHex;
 use bdk_wallet::bitcoin::io::Cursor;
+use bdk_wallet::bitcoin::opcodes::all::OP_RETURN;
+use bdk_wallet::bitcoin::opcodes::OP_0;
 use bdk_wallet::bitcoin::psbt::ExtractTxError;
-use bdk_wallet::bitcoin::secp256k1::Secp256k1;
+use bdk_wallet::bitcoin::script::Builder;
+use bdk_wallet::bitcoin::secp256k1::{Message, Secp256k1, XOnlyPublicKey};
+use bdk_wallet::bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bdk_wallet::bitcoin::taproot::Signature as TaprootSignature;
+use bdk_wallet::bitcoin::transaction::Version;
 use bdk_wallet::bitcoin::Address as BdkAddress;
+use bdk_wallet::bitcoin::Amount as BdkAmount;
+use bdk_wallet::bitcoin::CompressedPublicKey;
 use bdk_wallet::bitcoin::Network;
+use bdk_wallet::bitcoin::OutPoint as BdkOutPoint;
 use bdk_wallet::bitcoin::Psbt as BdkPsbt;
+use bdk_wallet::bitcoin::ScriptBuf as BdkScriptBuf;
+use bdk_wallet::bitcoin::Sequence;
 use bdk_wallet::bitcoin::Transaction as BdkTransaction;
 use bdk_wallet::bitcoin::TxIn as BdkTxIn;
 use bdk_wallet::bitcoin::TxOut as BdkTxOut;
+use bdk_wallet::bitcoin::Witness;
 use bdk_wallet::miniscript::psbt::PsbtExt;
+use bdk_wallet::psbt::PsbtUtils;
 use bdk_wallet::serde_json;
 
 use std::fmt::Display;
@@ -51,12 +75,130 @@ impl Address {
         self.0.to_qr_uri()
     }
 
+    pub fn to_bip21_uri(
+        &self,
+        amount: Option<Arc<Amount>>,
+        label: Option<String>,
+        message: Option<String>,
+    ) -> String {
+        let mut parameters: Vec<String> = Vec::new();
+        if let Some(amount) = amount {
+            parameters.push(format!("amount={}", format_btc(amount.0.to_sat())));
+        }
+        if let Some(label) = label {
+            parameters.push(format!("label={}", percent_encode(&label)));
+        }
+        if let Some(message) = message {
+            parameters.push(format!("message={}", percent_encode(&message)));
+        }
+
+        if parameters.is_empty() {
+            format!("bitcoin:{}", self.0)
+        } else {
+            format!("bitcoin:{}?{}", self.0, parameters.join("&"))
+        }
+    }
+
+    pub fn address_type(&self) -> Option<AddressType> {
+        self.0
+            .address_type()
+            .and_then(|address_type| match address_type {
+                BdkAddressType::P2pkh => Some(AddressType::P2pkh),
+                BdkAddressType::P2sh => Some(AddressType::P2sh),
+                BdkAddressType::P2wpkh => Some(AddressType::P2wpkh),
+                BdkAddressType::P2wsh => Some(AddressType::P2wsh),
+                BdkAddressType::P2tr => Some(AddressType::P2tr),
+                // The bdk::bitcoin::address::AddressType enum is non-exhaustive
+                _ => None,
+            })
+    }
+
     pub fn is_valid_for_network(&self, network: Network) -> bool {
-        let address_str = self.0.to_string();
-        if let Ok(unchecked_address) = address_str.parse::<BdkAddress<NetworkUnchecked>>() {
-            unchecked_address.is_valid_for_network(network)
+        self.0.as_unchecked().is_valid_for_network(network)
+    }
+
+    pub fn verify_message(
+        &self,
+        message: String,
+        signature: String,
+    ) -> Result<bool, MessageSignatureError> {
+        let invalid_encoding = |error_message: String| {
+            MessageSignatureError::InvalidSignatureEncoding { error_message }
+        };
+        let witness_bytes = STANDARD
+            .decode(signature)
+            .map_err(|e| invalid_encoding(e.to_string()))?;
+        let witness: Witness =
+            deserialize(&witness_bytes).map_err(|e| invalid_encoding(e.to_string()))?;
+
+        let script_pubkey = self.0.script_pubkey();
+        let to_spend = bip322_to_spend(&script_pubkey, &message);
+        let to_sign = bip322_to_sign(&to_spend);
+        let mut sighash_cache = SighashCache::new(&to_sign);
+        let secp = Secp256k1::verification_only();
+
+        if script_pubkey.is_p2wpkh() {
+            let (Some(signature), Some(public_key), 2) =
+                (witness.nth(0), witness.nth(1), witness.len())
+            else {
+                return Ok(false);
+            };
+            let (Ok(signature), Ok(public_key)) = (
+                EcdsaSignature::from_slice(signature),
+                CompressedPublicKey::from_slice(public_key),
+            ) else {
+                return Ok(false);
+            };
+            // BIP322 only allows SIGHASH_ALL signatures
+            if signature.sighash_type != EcdsaSighashType::All
+                || BdkScriptBuf::new_p2wpkh(&public_key.wpubkey_hash()) != script_pubkey
+            {
+                return Ok(false);
+            }
+            let Ok(sighash) = sighash_cache.p2wpkh_signature_hash(
+                0,
+                &script_pubkey,
+                BdkAmount::ZERO,
+                EcdsaSighashType::All,
+            ) else {
+                return Ok(false);
+            };
+            let message = Message::from_digest(sighash.to_byte_array());
+            Ok(secp
+                .verify_ecdsa(&message, &signature.signature, &public_key.0)
+                .is_ok())
+        } else if script_pubkey.is_p2tr() {
+            let (Some(signature), 1) = (witness.nth(0), witness.len()) else {
+                return Ok(false);
+            };
+            let Ok(signature) = TaprootSignature::from_slice(signature) else {
+                return Ok(false);
+            };
+            // BIP322 only allows SIGHASH_DEFAULT and SIGHASH_ALL signatures
+            if !matches!(
+                signature.sighash_type,
+                TapSighashType::Default | TapSighashType::All
+            ) {
+                return Ok(false);
+            }
+            // the witness program of a p2tr output is the x-only output key
+            let Ok(output_key) = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..]) else {
+                return Ok(false);
+            };
+            let prevouts = vec![to_spend.output[0].clone()];
+            let Ok(sighash) = sighash_cache.taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(prevouts.as_slice()),
+                signature.sighash_type,
+            ) else {
+                return Ok(false);
+            };
+            let message = Message::from_digest(sighash.to_byte_array());
+            Ok(secp
+                .verify_schnorr(&signature.signature, &message, &output_key)
+                .is_ok())
         } else {
-            false
+            Err(MessageSignatureError::UnsupportedAddressType)
         }
     }
 }
@@ -79,6 +221,193 @@ impl From<BdkAddress> for Address {
     }
 }
 
+// BIP21 amounts are denominated in BTC, written as a decimal without trailing zeros.
+fn format_btc(sats: u64) -> String {
+    let whole = sats / 100_000_000;
+    let fraction = sats % 100_000_000;
+    if fraction == 0 {
+        whole.to_string()
+    } else {
+        let fraction = format!("{:08}", fraction);
+        format!("{}.{}", whole, fraction.trim_end_matches('0'))
+    }
+}
+
+// Percent-encode everything but the RFC 3986 unreserved characters.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+}
+
+/// The fields of a BIP21 `bitcoin:` payment URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUri {
+    address: String,
+    amount_sats: Option<u64>,
+    label: Option<String>,
+    message: Option<String>,
+}
+
+impl PaymentUri {
+    pub fn from_string(uri: String) -> Result<Self, PaymentUriParseError> {
+        let (scheme, rest) = uri
+            .split_once(':')
+            .ok_or(PaymentUriParseError::MissingScheme)?;
+        if !scheme.eq_ignore_ascii_case("bitcoin") {
+            return Err(PaymentUriParseError::MissingScheme);
+        }
+
+        let (address, query) = match rest.split_once('?') {
+            Some((address, query)) => (address, Some(query)),
+            None => (rest, None),
+        };
+        if address.is_empty() {
+            return Err(PaymentUriParseError::MissingAddress);
+        }
+
+        let mut amount_sats = None;
+        let mut label = None;
+        let mut message = None;
+        for parameter in query.into_iter().flat_map(|query| query.split('&')) {
+            if parameter.is_empty() {
+                continue;
+            }
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            match key {
+                "amount" => {
+                    let amount =
+                        BdkAmount::from_str_in(value, Denomination::Bitcoin).map_err(|_| {
+                            PaymentUriParseError::InvalidAmount {
+                                amount: value.to_string(),
+                            }
+                        })?;
+                    amount_sats = Some(amount.to_sat());
+                }
+                "label" => label = Some(percent_decode(key, value)?),
+                "message" => message = Some(percent_decode(key, value)?),
+                // BIP21 requires rejecting the whole URI on any unknown req- parameter
+                _ if key.starts_with("req-") => {
+                    return Err(PaymentUriParseError::UnknownRequiredParameter {
+                        parameter: key.to_string(),
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Ok(PaymentUri {
+            address: address.to_string(),
+            amount_sats,
+            label,
+            message,
+        })
+    }
+
+    pub fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    pub fn amount_sats(&self) -> Option<u64> {
+        self.amount_sats
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    pub fn message(&self) -> Option<String> {
+        self.message.clone()
+    }
+}
+
+fn percent_decode(parameter: &str, value: &str) -> Result<String, PaymentUriParseError> {
+    let invalid = || PaymentUriParseError::InvalidPercentEncoding {
+        parameter: parameter.to_string(),
+    };
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3).ok_or_else(invalid)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err(invalid());
+            }
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+// The virtual transaction committing to the message and the signing address, as defined in BIP322.
+pub(crate) fn bip322_to_spend(script_pubkey: &BdkScriptBuf, message: &str) -> BdkTransaction {
+    let tag_hash = sha256::Hash::hash(b"BIP0322-signed-message");
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag_hash.as_ref());
+    engine.input(tag_hash.as_ref());
+    engine.input(message.as_bytes());
+    let message_hash = sha256::Hash::from_engine(engine);
+
+    BdkTransaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![BdkTxIn {
+            previous_output: BdkOutPoint::null(),
+            script_sig: Builder::new()
+                .push_opcode(OP_0)
+                .push_slice(message_hash.to_byte_array())
+                .into_script(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![BdkTxOut {
+            value: BdkAmount::ZERO,
+            script_pubkey: script_pubkey.clone(),
+        }],
+    }
+}
+
+// The virtual transaction whose input witness is the BIP322 simple signature.
+pub(crate) fn bip322_to_sign(to_spend: &BdkTransaction) -> BdkTransaction {
+    BdkTransaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![BdkTxIn {
+            previous_output: BdkOutPoint::new(to_spend.compute_txid(), 0),
+            script_sig: BdkScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![BdkTxOut {
+            value: BdkAmount::ZERO,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Transaction(BdkTransaction);
 
@@ -136,6 +465,37 @@ impl Transaction {
     pub fn lock_time(&self) -> u32 {
         self.0.lock_time.to_consensus_u32()
     }
+
+    pub fn vout_value(&self, index: u32) -> Result<u64, TransactionError> {
+        self.0
+            .output
+            .get(index as usize)
+            .map(|tx_out| tx_out.value.to_sat())
+            .ok_or(TransactionError::OutputIndexOutOfRange {
+                index,
+                outputs: self.0.output.len() as u64,
+            })
+    }
+
+    pub fn total_output_value(&self) -> u64 {
+        self.0
+            .output
+            .iter()
+            .map(|tx_out| tx_out.value.to_sat())
+            .sum()
+    }
+
+    pub fn output_addresses(&self, network: Network) -> Vec<Option<Arc<Address>>> {
+        self.0
+            .output
+            .iter()
+            .map(|tx_out| {
+                BdkAddress::from_script(&tx_out.script_pubkey, network)
+                    .ok()
+                    .map(|address| Arc::new(Address(address)))
+            })
+            .collect()
+    }
 }
 
 impl From<BdkTransaction> for Transaction {
@@ -164,11 +524,25 @@ impl Psbt {
         Ok(Psbt(Mutex::new(psbt)))
     }
 
+    pub(crate) fn from_hex(psbt_hex: String) -> Result<Self, PsbtParseError> {
+        let bytes = Vec::<u8>::from_hex(&psbt_hex).map_err(|e| PsbtParseError::HexEncoding {
+            error_message: e.to_string(),
+        })?;
+        let psbt = BdkPsbt::deserialize(&bytes).map_err(|e| PsbtParseError::PsbtEncoding {
+            error_message: e.to_string(),
+        })?;
+        Ok(Psbt(Mutex::new(psbt)))
+    }
+
     pub(crate) fn serialize(&self) -> String {
         let psbt = self.0.lock().unwrap().clone();
         psbt.to_string()
     }
 
+    pub(crate) fn serialize_hex(&self) -> String {
+        self.0.lock().unwrap().serialize_hex()
+    }
+
     pub(crate) fn extract_tx(&self) -> Result<Arc<Transaction>, ExtractTxError> {
         let tx: BdkTransaction = self.0.lock().unwrap().clone().extract_tx()?;
         let transaction: Transaction = tx.into();
@@ -184,6 +558,35 @@ impl Psbt {
             .map_err(PsbtError::from)
     }
 
+    pub(crate) fn compute_txid(&self) -> String {
+        self.0
+            .lock()
+            .unwrap()
+            .unsigned_tx
+            .compute_txid()
+            .to_string()
+    }
+
+    pub(crate) fn inputs_len(&self) -> u64 {
+        self.0.lock().unwrap().unsigned_tx.input.len() as u64
+    }
+
+    pub(crate) fn outputs_len(&self) -> u64 {
+        self.0.lock().unwrap().unsigned_tx.output.len() as u64
+    }
+
+    pub(crate) fn fee_amount(&self) -> Option<u64> {
+        self.0.lock().unwrap().fee_amount().map(|fee| fee.to_sat())
+    }
+
+    pub(crate) fn fee_rate(&self) -> Option<Arc<FeeRate>> {
+        self.0
+            .lock()
+            .unwrap()
+            .fee_rate()
+            .map(|fee_rate| Arc::new(FeeRate(fee_rate)))
+    }
+
     pub(crate) fn combine(&self, other: Arc<Psbt>) -> Result<Arc<Psbt>, PsbtError> {
         let mut original_psbt = self.0.lock().unwrap().clone();
         let other_psbt = other.0.lock().unwrap().clone();
@@ -269,7 +672,400 @@ impl From<&BdkTxOut> for TxOut {
 #[cfg(test)]
 mod tests {
     use crate::bitcoin::Address;
+    use crate::bitcoin::AddressType;
     use crate::bitcoin::Network;
+    use crate::bitcoin::PaymentUri;
+    use crate::bitcoin::Psbt;
+    use crate::bitcoin::Transaction;
+    use crate::bitcoin::{bip322_to_sign, bip322_to_spend};
+    use crate::error::MessageSignatureError;
+    use crate::error::PaymentUriParseError;
+    use crate::error::PsbtError;
+    use crate::error::PsbtParseError;
+    use crate::error::TransactionError;
+
+    use bitcoin_ffi::Amount;
+
+    use bdk_wallet::bitcoin::absolute::LockTime;
+    use bdk_wallet::bitcoin::base64::engine::general_purpose::STANDARD;
+    use bdk_wallet::bitcoin::base64::Engine;
+    use bdk_wallet::bitcoin::consensus::encode::serialize;
+    use bdk_wallet::bitcoin::ecdsa::Signature;
+    use bdk_wallet::bitcoin::hashes::Hash;
+    use bdk_wallet::bitcoin::hex::FromHex;
+    use bdk_wallet::bitcoin::key::{Keypair, TapTweak};
+    use bdk_wallet::bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bdk_wallet::bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+    use bdk_wallet::bitcoin::taproot::Signature as TaprootSignature;
+    use bdk_wallet::bitcoin::Address as BdkAddress;
+    use bdk_wallet::bitcoin::Amount as BdkAmount;
+    use bdk_wallet::bitcoin::CompressedPublicKey;
+    use bdk_wallet::bitcoin::Psbt as BdkPsbt;
+    use bdk_wallet::bitcoin::PublicKey;
+    use bdk_wallet::bitcoin::ScriptBuf;
+    use bdk_wallet::bitcoin::TxOut as BdkTxOut;
+    use bdk_wallet::bitcoin::Witness;
+    use bdk_wallet::serde_json;
+
+    use std::sync::{Arc, Mutex};
+
+    // The coinbase transaction of the genesis block
+    const GENESIS_COINBASE_TX: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+
+    // A version 2 transaction signalling RBF, locked to block 800000, with a P2WPKH and an
+    // OP_RETURN output
+    const RBF_TX: &str = "02000000013ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a0000000000fdffffff0250c3000000000000160014751e76e8199196d454941c45d1b3a323f1433bd600000000000000000d6a0b68656c6c6f20776f726c6400350c00";
+
+    fn decode_transaction(tx_hex: &str) -> Transaction {
+        let transaction_bytes = Vec::<u8>::from_hex(tx_hex).unwrap();
+        Transaction::new(transaction_bytes).unwrap()
+    }
+
+    // A PSBT spending RBF_TX's input, optionally with the witness utxo that input spends
+    fn rbf_psbt(input_value: Option<u64>) -> Psbt {
+        let transaction = decode_transaction(RBF_TX);
+        let mut psbt = BdkPsbt::from_unsigned_tx(transaction.0).unwrap();
+        psbt.inputs[0].witness_utxo = input_value.map(|value| BdkTxOut {
+            value: BdkAmount::from_sat(value),
+            script_pubkey: ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6")
+                .unwrap(),
+        });
+        Psbt(Mutex::new(psbt))
+    }
+
+    #[test]
+    fn test_transaction_compute_txid() {
+        let transaction = decode_transaction(GENESIS_COINBASE_TX);
+        assert_eq!(
+            transaction.compute_txid(),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+        // The txid of a transaction without witnesses is stable across a serialization round-trip
+        let round_trip = Transaction::new(transaction.serialize()).unwrap();
+        assert_eq!(round_trip.compute_txid(), transaction.compute_txid());
+    }
+
+    #[test]
+    fn test_transaction_input_and_output() {
+        let transaction = decode_transaction(GENESIS_COINBASE_TX);
+
+        let input = transaction.input();
+        assert_eq!(input.len(), 1);
+        assert_eq!(input[0].previous_output.vout, u32::MAX);
+        assert_eq!(input[0].script_sig.0.len(), 77);
+        assert_eq!(input[0].sequence, 0xffffffff);
+        assert!(input[0].witness.is_empty());
+
+        let output = transaction.output();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].value, 5_000_000_000);
+        assert!(output[0].script_pubkey.0.is_p2pk());
+    }
+
+    #[test]
+    fn test_transaction_protocol_fields() {
+        let coinbase = decode_transaction(GENESIS_COINBASE_TX);
+        assert_eq!(coinbase.version(), 1);
+        assert_eq!(coinbase.lock_time(), 0);
+        assert!(coinbase.is_coinbase());
+        assert!(!coinbase.is_explicitly_rbf());
+
+        let transaction = decode_transaction(RBF_TX);
+        assert_eq!(transaction.version(), 2);
+        assert_eq!(transaction.lock_time(), 800_000);
+        assert!(!transaction.is_coinbase());
+        assert!(transaction.is_explicitly_rbf());
+        assert!(transaction.is_lock_time_enabled());
+    }
+
+    #[test]
+    fn test_transaction_output_values() {
+        let coinbase = decode_transaction(GENESIS_COINBASE_TX);
+        assert_eq!(coinbase.total_output_value(), 5_000_000_000);
+
+        let transaction = decode_transaction(RBF_TX);
+        assert_eq!(transaction.vout_value(0).unwrap(), 50_000);
+        assert_eq!(transaction.vout_value(1).unwrap(), 0);
+        assert!(matches!(
+            transaction.vout_value(2),
+            Err(TransactionError::OutputIndexOutOfRange {
+                index: 2,
+                outputs: 2
+            })
+        ));
+        assert_eq!(transaction.total_output_value(), 50_000);
+    }
+
+    #[test]
+    fn test_transaction_output_addresses() {
+        let transaction = decode_transaction(RBF_TX);
+
+        let addresses = transaction.output_addresses(Network::Bitcoin);
+        assert_eq!(addresses.len(), transaction.output().len());
+        assert_eq!(
+            addresses[0].as_ref().unwrap().to_string(),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        // OP_RETURN outputs have no address
+        assert!(addresses[1].is_none());
+
+        let addresses = transaction.output_addresses(Network::Testnet);
+        assert_eq!(
+            addresses[0].as_ref().unwrap().to_string(),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+        );
+    }
+
+    #[test]
+    fn test_verify_message() {
+        // test vectors from BIP322
+        let p2wpkh = Address::new(
+            "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l".to_string(),
+            Network::Bitcoin,
+        )
+        .unwrap();
+        let empty_signature = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+        let hello_signature = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+        assert!(p2wpkh
+            .verify_message("".to_string(), empty_signature.to_string())
+            .unwrap());
+        assert!(p2wpkh
+            .verify_message("Hello World".to_string(), hello_signature.to_string())
+            .unwrap());
+        assert!(!p2wpkh
+            .verify_message("Hello World!".to_string(), hello_signature.to_string())
+            .unwrap());
+        assert!(!p2wpkh
+            .verify_message("".to_string(), hello_signature.to_string())
+            .unwrap());
+
+        let p2tr = Address::new(
+            "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3".to_string(),
+            Network::Bitcoin,
+        )
+        .unwrap();
+        let taproot_signature = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+        assert!(p2tr
+            .verify_message("Hello World".to_string(), taproot_signature.to_string())
+            .unwrap());
+        assert!(!p2tr
+            .verify_message("Hello World!".to_string(), taproot_signature.to_string())
+            .unwrap());
+        // a p2wpkh signature does not verify against a p2tr address
+        assert!(!p2tr
+            .verify_message("Hello World".to_string(), hello_signature.to_string())
+            .unwrap());
+
+        assert!(matches!(
+            p2wpkh.verify_message("Hello World".to_string(), "not base64!".to_string()),
+            Err(MessageSignatureError::InvalidSignatureEncoding { .. })
+        ));
+
+        let p2pkh = Address::new(
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_string(),
+            Network::Bitcoin,
+        )
+        .unwrap();
+        assert!(matches!(
+            p2pkh.verify_message("Hello World".to_string(), hello_signature.to_string()),
+            Err(MessageSignatureError::UnsupportedAddressType)
+        ));
+    }
+
+    #[test]
+    fn test_verify_message_rejects_non_sighash_all() {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let public_key = CompressedPublicKey(secret_key.public_key(&secp));
+        let address = Address::from(BdkAddress::p2wpkh(&public_key, Network::Bitcoin));
+        let script_pubkey = address.0.script_pubkey();
+
+        // A signature that is valid for the message, but committing with SIGHASH_NONE
+        let to_spend = bip322_to_spend(&script_pubkey, "Hello World");
+        let to_sign = bip322_to_sign(&to_spend);
+        let sighash = SighashCache::new(&to_sign)
+            .p2wpkh_signature_hash(0, &script_pubkey, BdkAmount::ZERO, EcdsaSighashType::None)
+            .unwrap();
+        let signature = Signature {
+            signature: secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &secret_key),
+            sighash_type: EcdsaSighashType::None,
+        };
+        let witness = Witness::p2wpkh(&signature, &public_key.0);
+        let encoded = STANDARD.encode(serialize(&witness));
+
+        assert!(!address
+            .verify_message("Hello World".to_string(), encoded)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_verify_message_rejects_non_default_taproot_sighash() {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_seckey_slice(&secp, &[1; 32]).unwrap();
+        let (internal_key, _) = keypair.x_only_public_key();
+        let address = Address::from(BdkAddress::p2tr(
+            &secp,
+            internal_key,
+            None,
+            Network::Bitcoin,
+        ));
+        let script_pubkey = address.0.script_pubkey();
+
+        // A key path signature that is valid for the message, but committing with SIGHASH_SINGLE
+        let to_spend = bip322_to_spend(&script_pubkey, "Hello World");
+        let to_sign = bip322_to_sign(&to_spend);
+        let prevouts = vec![to_spend.output[0].clone()];
+        let sighash = SighashCache::new(&to_sign)
+            .taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(prevouts.as_slice()),
+                TapSighashType::Single,
+            )
+            .unwrap();
+        let tweaked = keypair.tap_tweak(&secp, None).to_inner();
+        let signature = TaprootSignature {
+            signature: secp
+                .sign_schnorr_no_aux_rand(&Message::from_digest(sighash.to_byte_array()), &tweaked),
+            sighash_type: TapSighashType::Single,
+        };
+        let witness = Witness::p2tr_key_spend(&signature);
+        let encoded = STANDARD.encode(serialize(&witness));
+
+        assert!(!address
+            .verify_message("Hello World".to_string(), encoded)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_to_bip21_uri() {
+        let address = Address::new(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            Network::Bitcoin,
+        )
+        .unwrap();
+
+        assert_eq!(
+            address.to_bip21_uri(None, None, None),
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            address.to_bip21_uri(
+                Some(Arc::new(Amount::from_sat(50_000))),
+                Some("Luke-Jr".to_string()),
+                Some("Donation for project xyz".to_string()),
+            ),
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=0.0005&label=Luke-Jr&message=Donation%20for%20project%20xyz"
+        );
+        assert_eq!(
+            address.to_bip21_uri(Some(Arc::new(Amount::from_sat(2_100_000_000))), None, None),
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=21"
+        );
+        assert_eq!(
+            address.to_bip21_uri(Some(Arc::new(Amount::from_sat(1))), None, None),
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=0.00000001"
+        );
+    }
+
+    #[test]
+    fn test_payment_uri_address_only() {
+        let uri = PaymentUri::from_string(
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(uri.address(), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        assert_eq!(uri.amount_sats(), None);
+        assert_eq!(uri.label(), None);
+        assert_eq!(uri.message(), None);
+    }
+
+    #[test]
+    fn test_payment_uri_all_fields() {
+        let uri = PaymentUri::from_string(
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=20.3&label=Luke-Jr&message=Donation%20for%20project%20xyz&somethingyoudontunderstand=50"
+                .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(uri.address(), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        assert_eq!(uri.amount_sats(), Some(2_030_000_000));
+        assert_eq!(uri.label(), Some("Luke-Jr".to_string()));
+        assert_eq!(uri.message(), Some("Donation for project xyz".to_string()));
+    }
+
+    #[test]
+    fn test_payment_uri_round_trip() {
+        let address = Address::new(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            Network::Bitcoin,
+        )
+        .unwrap();
+        let uri = address.to_bip21_uri(
+            Some(Arc::new(Amount::from_sat(12_345))),
+            Some("café & co".to_string()),
+            None,
+        );
+        let parsed = PaymentUri::from_string(uri).unwrap();
+
+        assert_eq!(parsed.address(), address.to_string());
+        assert_eq!(parsed.amount_sats(), Some(12_345));
+        assert_eq!(parsed.label(), Some("café & co".to_string()));
+    }
+
+    #[test]
+    fn test_payment_uri_errors() {
+        let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+        assert!(matches!(
+            PaymentUri::from_string(format!("litecoin:{}", address)),
+            Err(PaymentUriParseError::MissingScheme)
+        ));
+        assert!(matches!(
+            PaymentUri::from_string("bitcoin:?amount=1".to_string()),
+            Err(PaymentUriParseError::MissingAddress)
+        ));
+        assert!(matches!(
+            PaymentUri::from_string(format!("bitcoin:{}?amount=-1", address)),
+            Err(PaymentUriParseError::InvalidAmount { .. })
+        ));
+        assert!(matches!(
+            PaymentUri::from_string(format!("bitcoin:{}?amount=0.000000001", address)),
+            Err(PaymentUriParseError::InvalidAmount { .. })
+        ));
+        assert!(matches!(
+            PaymentUri::from_string(format!("bitcoin:{}?label=%zz", address)),
+            Err(PaymentUriParseError::InvalidPercentEncoding { .. })
+        ));
+        assert!(matches!(
+            PaymentUri::from_string(format!("bitcoin:{}?req-somethingyoudontunderstand=50", address)),
+            Err(PaymentUriParseError::UnknownRequiredParameter { parameter }) if parameter == "req-somethingyoudontunderstand"
+        ));
+    }
+
+    #[test]
+    fn test_address_type() {
+        let cases = vec![
+            ("1FfmbHfnpaZjKFvyi1okTjJJusN455paPH", AddressType::P2pkh),
+            ("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", AddressType::P2sh),
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                AddressType::P2wpkh,
+            ),
+            (
+                "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+                AddressType::P2wsh,
+            ),
+            (
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                AddressType::P2tr,
+            ),
+        ];
+
+        for (address, expected_type) in cases {
+            let address = Address::new(address.to_string(), Network::Bitcoin).unwrap();
+            assert_eq!(address.address_type(), Some(expected_type));
+        }
+    }
 
     #[test]
     fn test_is_valid_for_network() {
@@ -595,4 +1391,152 @@ mod tests {
             "Address should be valid for Regtest"
         );
     }
+
+    #[test]
+    fn test_psbt_fee_amount_and_fee_rate() {
+        let psbt = rbf_psbt(Some(60_000));
+        assert_eq!(psbt.fee_amount(), Some(10_000));
+
+        // 10,000 sats over 104 vbytes
+        let fee_rate = psbt.fee_rate().unwrap();
+        assert_eq!(fee_rate.0.to_sat_per_vb_floor(), 96);
+        assert_eq!(fee_rate.0.to_sat_per_vb_ceil(), 97);
+    }
+
+    #[test]
+    fn test_psbt_fee_rate_before_finalization() {
+        let psbt = rbf_psbt(Some(60_000));
+        let unsigned_fee_rate = psbt.fee_rate().unwrap();
+
+        // a p2wpkh witness: a 72 byte signature and a 33 byte compressed public key
+        psbt.0.lock().unwrap().inputs[0].final_script_witness =
+            Some(Witness::from_slice(&[vec![0; 72], vec![2; 33]]));
+        let finalized_fee_rate = psbt.fee_rate().unwrap();
+
+        // the unsigned rate leaves out the witness, so it overestimates the rate actually paid:
+        // 10,000 sats over 131.5 vbytes
+        assert!(finalized_fee_rate.0 < unsigned_fee_rate.0);
+        assert_eq!(finalized_fee_rate.0.to_sat_per_vb_floor(), 76);
+    }
+
+    #[test]
+    fn test_psbt_fee_without_utxo_information() {
+        let psbt = rbf_psbt(None);
+        assert_eq!(psbt.fee_amount(), None);
+        assert!(psbt.fee_rate().is_none());
+    }
+
+    #[test]
+    fn test_psbt_combine() {
+        let secp = Secp256k1::new();
+        let message = Message::from_digest([1; 32]);
+        let partially_signed = |secret: [u8; 32]| {
+            let secret_key = SecretKey::from_slice(&secret).unwrap();
+            let signature = Signature {
+                signature: secp.sign_ecdsa(&message, &secret_key),
+                sighash_type: EcdsaSighashType::All,
+            };
+            let psbt = rbf_psbt(Some(60_000));
+            psbt.0.lock().unwrap().inputs[0]
+                .partial_sigs
+                .insert(PublicKey::new(secret_key.public_key(&secp)), signature);
+            psbt
+        };
+
+        let first = partially_signed([1; 32]);
+        let second = partially_signed([2; 32]);
+        let combined = first.combine(Arc::new(second)).unwrap();
+
+        assert_eq!(combined.0.lock().unwrap().inputs[0].partial_sigs.len(), 2);
+        // The original PSBT is left untouched
+        assert_eq!(first.0.lock().unwrap().inputs[0].partial_sigs.len(), 1);
+    }
+
+    #[test]
+    fn test_psbt_combine_different_transactions() {
+        let mut transaction = decode_transaction(RBF_TX).0;
+        transaction.lock_time = LockTime::ZERO;
+        let other = Psbt(Mutex::new(BdkPsbt::from_unsigned_tx(transaction).unwrap()));
+
+        let result = rbf_psbt(Some(60_000)).combine(Arc::new(other));
+        assert!(matches!(result, Err(PsbtError::UnexpectedUnsignedTx)));
+    }
+
+    #[test]
+    fn test_psbt_extract_tx() {
+        let psbt = rbf_psbt(Some(60_000));
+        psbt.0.lock().unwrap().inputs[0].final_script_witness =
+            Some(Witness::from_slice(&[vec![1u8; 72], vec![2u8; 33]]));
+
+        let transaction = psbt.extract_tx().unwrap();
+        // Extracting clones the PSBT, so it can be done more than once
+        let again = psbt.extract_tx().unwrap();
+        assert_eq!(transaction.serialize(), again.serialize());
+
+        let serialized = transaction.serialize();
+        assert!(!serialized.is_empty());
+        let decoded = Transaction::new(serialized).unwrap();
+        assert_eq!(decoded.input()[0].witness, vec![vec![1; 72], vec![2; 33]]);
+        assert_eq!(
+            decoded.compute_txid(),
+            "652a942c394fb9c12b5ee4a739527a4ba66e1fb4a3630a4ce2e7fdcce9ce9315"
+        );
+    }
+
+    #[test]
+    fn test_psbt_hex_round_trip() {
+        let psbt = rbf_psbt(Some(60_000));
+        let psbt_hex = psbt.serialize_hex();
+        // Every PSBT starts with the magic bytes "psbt" followed by 0xff
+        assert!(psbt_hex.starts_with("70736274ff"));
+
+        let reparsed = Psbt::from_hex(psbt_hex).unwrap();
+        assert_eq!(reparsed.serialize(), psbt.serialize());
+
+        assert!(matches!(
+            Psbt::from_hex("70736274f".to_string()),
+            Err(PsbtParseError::HexEncoding { .. })
+        ));
+        assert!(matches!(
+            Psbt::from_hex("70736274".to_string()),
+            Err(PsbtParseError::PsbtEncoding { .. })
+        ));
+    }
+
+    #[test]
+    fn test_psbt_json_serialize() {
+        let json = rbf_psbt(Some(60_000)).json_serialize();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        for key in [
+            "unsigned_tx",
+            "version",
+            "xpub",
+            "proprietary",
+            "unknown",
+            "inputs",
+            "outputs",
+        ] {
+            assert!(value.get(key).is_some(), "missing top-level key {}", key);
+        }
+
+        let inputs = value["inputs"].as_array().unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert!(!inputs[0]["witness_utxo"].is_null());
+        assert!(inputs[0].get("partial_sigs").is_some());
+        let outputs = value["outputs"].as_array().unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert!(outputs[0].get("bip32_derivation").is_some());
+    }
+
+    #[test]
+    fn test_psbt_unsigned_tx_metadata() {
+        let psbt = rbf_psbt(None);
+        assert_eq!(
+            psbt.compute_txid(),
+            "652a942c394fb9c12b5ee4a739527a4ba66e1fb4a3630a4ce2e7fdcce9ce9315"
+        );
+        assert_eq!(psbt.inputs_len(), 1);
+        assert_eq!(psbt.outputs_len(), 2);
+    }
 }
@@ -5,7 +5,7 @@ use crate::keys::DescriptorSecretKey;
 use bdk_wallet::bitcoin::bip32::Fingerprint;
 use bdk_wallet::bitcoin::key::Secp256k1;
 use bdk_wallet::bitcoin::Network;
-use bdk_wallet::descriptor::{ExtendedDescriptor, IntoWalletDescriptor};
+use bdk_wallet::descriptor::{calc_checksum, ExtendedDescriptor, IntoWalletDescriptor};
 use bdk_wallet::keys::DescriptorPublicKey as BdkDescriptorPublicKey;
 use bdk_wallet::keys::{DescriptorSecretKey as BdkDescriptorSecretKey, KeyMap};
 use bdk_wallet::template::{
@@ -273,6 +273,13 @@ impl Descriptor {
         self.extended_descriptor.is_multipath()
     }
 
+    pub(crate) fn max_weight_to_satisfy(&self) -> Result<u64, MiniscriptError> {
+        self.extended_descriptor
+            .max_weight_to_satisfy()
+            .map(|weight| weight.to_wu())
+            .map_err(MiniscriptError::from)
+    }
+
     pub(crate) fn to_single_descriptors(&self) -> Result<Vec<Arc<Descriptor>>, MiniscriptError> {
         self.extended_descriptor
             .clone()
@@ -292,6 +299,10 @@ impl Descriptor {
     }
 }
 
+pub fn calculate_descriptor_checksum(descriptor: String) -> Result<String, DescriptorError> {
+    Ok(calc_checksum(&descriptor)?)
+}
+
 impl Display for Descriptor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.extended_descriptor)
@@ -305,6 +316,8 @@ mod test {
     use bdk_wallet::bitcoin::Network;
     use bdk_wallet::KeychainKind;
 
+    use std::sync::Arc;
+
     fn get_descriptor_secret_key() -> DescriptorSecretKey {
         let mnemonic = Mnemonic::from_string("chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect".to_string()).unwrap();
         DescriptorSecretKey::new(Network::Testnet, &mnemonic, None)
@@ -414,7 +427,86 @@ mod test {
             template_private_86.to_string(),
             template_public_86.to_string()
         );
+        // the bip84 template uses the bip84 derivation path and a native segwit script
+        assert_eq!(
+            template_private_84.to_string(),
+            "wpkh([d1d04177/84'/1'/0']tpubDDNxbq17egjFk2edjv8oLnzxk52zny9aAYNv9CMqTzA4mQDiQq818sEkNe9Gzmd4QU8558zftqbfoVBDQorG3E4Wq26tB2JeE4KUoahLkx6/0/*)#nudvntrc"
+        );
+    }
+    #[test]
+    fn test_bip86_taproot_addresses() {
+        // test vectors from BIP86
+        let mnemonic = Mnemonic::from_string("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string()).unwrap();
+        let master = DescriptorSecretKey::new(Network::Bitcoin, &mnemonic, None);
+        let descriptor = Descriptor::new_bip86(&master, KeychainKind::External, Network::Bitcoin);
+        let change_descriptor =
+            Descriptor::new_bip86(&master, KeychainKind::Internal, Network::Bitcoin);
+        assert!(descriptor
+            .to_string()
+            .starts_with("tr([73c5da0a/86'/0'/0']xpub"));
+        let wallet = Wallet::new(
+            Arc::new(descriptor),
+            Arc::new(change_descriptor),
+            Network::Bitcoin,
+            Arc::new(Connection::new_in_memory().unwrap()),
+        )
+        .unwrap();
+
+        let address = wallet.peek_address(KeychainKind::External, 0).address;
+        assert_eq!(
+            address.to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+        assert_eq!(address.address_type(), Some(AddressType::P2tr));
+        let change_address = wallet.peek_address(KeychainKind::Internal, 0).address;
+        assert_eq!(
+            change_address.to_string(),
+            "bc1p3qkhfews2uk44qtvauqyr2ttdsw7svhkl9nkm9s9c3x4ax5h60wqwruhk7"
+        );
+    }
+
+    #[test]
+    fn test_descriptor_to_string_with_secret() {
+        let master: DescriptorSecretKey = get_descriptor_secret_key();
+        let descriptor = Descriptor::new_bip84(&master, KeychainKind::External, Network::Testnet);
+        // the watch-only form only contains the public key
+        let public_string = descriptor.to_string();
+        assert!(!public_string.contains("tprv"));
+        assert!(public_string.contains("tpub"));
+        // the private form keeps the secret key, and both forms carry a checksum
+        let private_string = descriptor.to_string_with_secret();
+        assert_eq!(
+            private_string,
+            "wpkh(tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/84'/1'/0'/0/*)#fnf09k4v"
+        );
+        // parsing the private form back yields the same descriptor
+        let parsed = Descriptor::new(private_string, Network::Testnet).unwrap();
+        assert_eq!(parsed.to_string(), public_string);
     }
+
+    #[test]
+    fn test_calculate_descriptor_checksum() {
+        let descriptor = "wpkh(tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/84'/1'/0'/0/*)";
+        assert_eq!(
+            calculate_descriptor_checksum(descriptor.to_string()).unwrap(),
+            "fnf09k4v"
+        );
+        assert!(Descriptor::new(format!("{}#fnf09k4v", descriptor), Network::Testnet).is_ok());
+        // a single mistyped character of the checksum is caught
+        assert_matches!(
+            Descriptor::new(format!("{}#fnf09k4w", descriptor), Network::Testnet).unwrap_err(),
+            DescriptorError::InvalidDescriptorChecksum
+        );
+    }
+
+    #[test]
+    fn test_max_weight_to_satisfy() {
+        let descriptor = Descriptor::new("wpkh(tprv8hwWMmPE4BVNxGdVt3HhEERZhondQvodUY7Ajyseyhudr4WabJqWKWLr4Wi2r26CDaNCQhhxEftEaNzz7dPGhWuKFU4VULesmhEfZYyBXdE/0/*)".to_string(), Network::Testnet).unwrap();
+        // a signature with its sighash byte (73) and a compressed public key (34), each with its
+        // length prefix
+        assert_eq!(descriptor.max_weight_to_satisfy().unwrap(), 107);
+    }
+
     #[test]
     fn test_descriptor_from_string() {
         let descriptor1 = Descriptor::new("wpkh(tprv8hwWMmPE4BVNxGdVt3HhEERZhondQvodUY7Ajyseyhudr4WabJqWKWLr4Wi2r26CDaNCQhhxEftEaNzz7dPGhWuKFU4VULesmhEfZYyBXdE/0/*)".to_string(), Network::Testnet);
@@ -422,5 +514,30 @@ mod test {
         // Creating a Descriptor using an extended key that doesn't match the network provided will throw a DescriptorError::Key with inner InvalidNetwork error
         assert!(descriptor1.is_ok());
         assert_matches!(descriptor2.unwrap_err(), DescriptorError::Key { .. });
+
+        // The same applies to a mainnet public key imported on testnet
+        let descriptor3 = Descriptor::new("wpkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*)".to_string(), Network::Testnet);
+        assert_matches!(descriptor3.unwrap_err(), DescriptorError::Key { .. });
+    }
+
+    #[test]
+    fn test_to_single_descriptors() {
+        let multipath = Descriptor::new("wpkh(tprv8hwWMmPE4BVNxGdVt3HhEERZhondQvodUY7Ajyseyhudr4WabJqWKWLr4Wi2r26CDaNCQhhxEftEaNzz7dPGhWuKFU4VULesmhEfZYyBXdE/<0;1>/*)".to_string(), Network::Testnet).unwrap();
+        assert!(multipath.is_multipath());
+        let descriptors = multipath.to_single_descriptors().unwrap();
+        assert_eq!(descriptors.len(), 2);
+        assert!(!descriptors[0].is_multipath());
+        assert!(descriptors[0].to_string().contains("/0/*)"));
+        assert!(!descriptors[1].is_multipath());
+        assert!(descriptors[1].to_string().contains("/1/*)"));
+        // the secret key is carried over to every single descriptor
+        assert!(descriptors[1].to_string_with_secret().contains("tprv"));
+
+        // a regular descriptor expands to itself
+        let single = Descriptor::new("wpkh(tprv8hwWMmPE4BVNxGdVt3HhEERZhondQvodUY7Ajyseyhudr4WabJqWKWLr4Wi2r26CDaNCQhhxEftEaNzz7dPGhWuKFU4VULesmhEfZYyBXdE/0/*)".to_string(), Network::Testnet).unwrap();
+        assert!(!single.is_multipath());
+        let descriptors = single.to_single_descriptors().unwrap();
+        assert_eq!(descriptors.len(), 1);
+        assert_eq!(descriptors[0].to_string(), single.to_string());
     }
 }
@@ -8,6 +8,7 @@ use bdk_core::spk_client::FullScanResponse as BdkFullScanResponse;
 use bdk_core::spk_client::SyncRequest as BdkSyncRequest;
 use bdk_core::spk_client::SyncResponse as BdkSyncResponse;
 use bdk_electrum::electrum_client::ServerFeaturesRes as BdkServerFeaturesRes;
+use bdk_electrum::electrum_client::{Config, ConfigBuilder, Socks5Config};
 use bdk_electrum::BdkElectrumClient as BdkBdkElectrumClient;
 use bdk_wallet::bitcoin::Transaction as BdkTransaction;
 use bdk_wallet::KeychainKind;
@@ -24,9 +25,14 @@ use std::sync::Arc;
 pub struct ElectrumClient(BdkBdkElectrumClient<bdk_electrum::electrum_client::Client>);
 
 impl ElectrumClient {
-    pub fn new(url: String) -> Result<Self, ElectrumError> {
+    pub fn new(
+        url: String,
+        socks5: Option<String>,
+        timeout: Option<u64>,
+    ) -> Result<Self, ElectrumError> {
+        let config = config_with_options(socks5, timeout)?;
         let inner_client: bdk_electrum::electrum_client::Client =
-            bdk_electrum::electrum_client::Client::new(url.as_str())?;
+            bdk_electrum::electrum_client::Client::from_config(url.as_str(), config)?;
         let client = BdkBdkElectrumClient::new(inner_client);
         Ok(Self(client))
     }
@@ -134,3 +140,74 @@ impl From<BdkServerFeaturesRes> for ServerFeaturesRes {
         }
     }
 }
+
+fn config_with_options(
+    socks5: Option<String>,
+    timeout: Option<u64>,
+) -> Result<Config, ElectrumError> {
+    // the electrum client only supports timeouts of up to 255 seconds
+    let timeout = timeout
+        .map(|timeout| u8::try_from(timeout).map_err(|_| ElectrumError::InvalidTimeout { timeout }))
+        .transpose()?;
+    Ok(ConfigBuilder::new()
+        .socks5(socks5.map(Socks5Config::new))
+        .timeout(timeout)
+        .build())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::electrum::{config_with_options, ElectrumClient};
+    use crate::error::ElectrumError;
+
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_config_with_socks5_proxy() {
+        let config = config_with_options(Some("127.0.0.1:9050".to_string()), None).unwrap();
+        assert_eq!(
+            config.socks5().as_ref().map(|socks5| socks5.addr.as_str()),
+            Some("127.0.0.1:9050")
+        );
+
+        let config = config_with_options(None, None).unwrap();
+        assert!(config.socks5().is_none());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_unreachable_socks5_proxy() {
+        let result = ElectrumClient::new(
+            "tcp://electrum.blockstream.info:60001".to_string(),
+            Some("127.0.0.1:1".to_string()),
+            None,
+        );
+
+        assert!(matches!(result, Err(ElectrumError::IOError { .. })));
+    }
+
+    #[test]
+    fn test_config_with_timeout() {
+        let config = config_with_options(None, Some(5)).unwrap();
+        assert_eq!(config.timeout(), Some(Duration::from_secs(5)));
+
+        assert!(matches!(
+            config_with_options(None, Some(256)),
+            Err(ElectrumError::InvalidTimeout { timeout: 256 })
+        ));
+
+        let config = config_with_options(None, None).unwrap();
+        assert_eq!(config.timeout(), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_timeout() {
+        // 10.255.255.1 is not routable, so without a timeout the connection attempt would hang
+        let start = Instant::now();
+        let result = ElectrumClient::new("tcp://10.255.255.1:50001".to_string(), None, Some(1));
+
+        assert!(matches!(result, Err(ElectrumError::IOError { .. })));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -173,9 +173,6 @@ pub enum CreateTxError {
     #[error("change policy descriptor error")]
     ChangePolicyDescriptor,
 
-    #[error("coin selection failed: {error_message}")]
-    CoinSelection { error_message: String },
-
     #[error("insufficient funds: needed {needed} sat, available {available} sat")]
     InsufficientFunds { needed: u64, available: u64 },
 
@@ -191,6 +188,15 @@ pub enum CreateTxError {
     #[error("reference to an unknown utxo: {outpoint}")]
     UnknownUtxo { outpoint: String },
 
+    #[error("utxo {outpoint} is frozen and cannot be spent")]
+    FrozenUtxo { outpoint: String },
+
+    #[error("max inputs must be at least 2 to consolidate utxos, got {max_inputs}")]
+    InvalidMaxInputs { max_inputs: u32 },
+
+    #[error("not enough utxos worth spending to consolidate: found {available}, need at least 2")]
+    NotEnoughUtxosToConsolidate { available: u32 },
+
     #[error("missing non-witness utxo for outpoint: {outpoint}")]
     MissingNonWitnessUtxo { outpoint: String },
 
@@ -200,8 +206,20 @@ pub enum CreateTxError {
     #[error("attempt to prepare too many bytes to be pushed into script")]
     PushBytesError,
 
+    #[error("OP_RETURN data of {length} bytes exceeds the standard limit of 80 bytes")]
+    OpReturnDataTooLarge { length: u64 },
+
     #[error("invalid lock time value")]
     LockTimeConversionError,
+
+    #[error("signing the transaction failed: {error_message}")]
+    Signer { error_message: String },
+
+    #[error("the transaction being bumped has no output with script pubkey {script_pubkey}")]
+    MissingScriptPubKey { script_pubkey: String },
+
+    #[error("invalid foreign utxo: {error_message}")]
+    InvalidForeignUtxo { error_message: String },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -268,6 +286,9 @@ pub enum DescriptorKeyError {
 
     #[error("error bip 32 related: {error_message}")]
     Bip32 { error_message: String },
+
+    #[error("the key is for a different network")]
+    InvalidNetwork,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -322,6 +343,9 @@ pub enum ElectrumError {
 
     #[error("the request has already been consumed")]
     RequestAlreadyConsumed,
+
+    #[error("timeout of {timeout} seconds is above the maximum of 255 seconds")]
+    InvalidTimeout { timeout: u64 },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -367,6 +391,9 @@ pub enum EsploraError {
 
     #[error("the server sent an invalid response")]
     InvalidResponse,
+
+    #[error("no fee estimate available for a confirmation target of {target} blocks")]
+    FeeEstimateNotFound { target: u64 },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -406,6 +433,12 @@ pub enum FromScriptError {
 pub enum RequestBuilderError {
     #[error("the request has already been consumed")]
     RequestAlreadyConsumed,
+
+    #[error("invalid spk range: from index {from_index} is greater than to index {to_index}")]
+    InvalidSpkRange { from_index: u32, to_index: u32 },
+
+    #[error("spk range too large: at most {max} indices can be synced at once")]
+    SpkRangeTooLarge { max: u32 },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -420,6 +453,24 @@ pub enum LoadWithPersistError {
     CouldNotLoad,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum MessageSignatureError {
+    #[error("the address does not belong to this wallet")]
+    AddressNotInWallet,
+
+    #[error("message signatures are only supported for p2wpkh and p2tr addresses")]
+    UnsupportedAddressType,
+
+    #[error("signing error: {error_message}")]
+    Signer { error_message: String },
+
+    #[error("the wallet could not produce a complete signature")]
+    IncompleteSignature,
+
+    #[error("invalid signature encoding: {error_message}")]
+    InvalidSignatureEncoding { error_message: String },
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum MiniscriptError {
     #[error("absolute locktime error")]
@@ -534,6 +585,24 @@ pub enum MiniscriptError {
     Unprintable { byte: u8 },
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentUriParseError {
+    #[error("uri does not use the bitcoin: scheme")]
+    MissingScheme,
+
+    #[error("uri does not contain an address")]
+    MissingAddress,
+
+    #[error("invalid amount: {amount}")]
+    InvalidAmount { amount: String },
+
+    #[error("invalid percent-encoding in parameter: {parameter}")]
+    InvalidPercentEncoding { parameter: String },
+
+    #[error("unknown required parameter: {parameter}")]
+    UnknownRequiredParameter { parameter: String },
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum PersistenceError {
     #[error("writing to persistence error: {error_message}")]
@@ -651,6 +720,9 @@ pub enum PsbtParseError {
 
     #[error("error in psbt base64 encoding: {error_message}")]
     Base64Encoding { error_message: String },
+
+    #[error("error in psbt hex encoding: {error_message}")]
+    HexEncoding { error_message: String },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -743,6 +815,9 @@ pub enum TransactionError {
     #[error("unsupported segwit version: {flag}")]
     UnsupportedSegwitFlag { flag: u8 },
 
+    #[error("output index {index} is out of range for a transaction with {outputs} outputs")]
+    OutputIndexOutOfRange { index: u32, outputs: u64 },
+
     // This is required because the bdk::bitcoin::consensus::encode::Error is non-exhaustive
     #[error("other transaction error")]
     OtherTransactionErr,
@@ -938,8 +1013,9 @@ impl From<BdkCreateTxError> for CreateTxError {
             BdkCreateTxError::OutputBelowDustLimit(index) => CreateTxError::OutputBelowDustLimit {
                 index: index as u64,
             },
-            BdkCreateTxError::CoinSelection(e) => CreateTxError::CoinSelection {
-                error_message: e.to_string(),
+            BdkCreateTxError::CoinSelection(e) => CreateTxError::InsufficientFunds {
+                needed: e.needed.to_sat(),
+                available: e.available.to_sat(),
             },
             BdkCreateTxError::NoRecipients => CreateTxError::NoRecipients,
             BdkCreateTxError::Psbt(e) => CreateTxError::Psbt {
@@ -1466,9 +1542,10 @@ impl From<BdkSqliteError> for SqliteError {
 #[cfg(test)]
 mod test {
     use crate::error::{
-        Bip32Error, Bip39Error, CannotConnectError, DescriptorError, DescriptorKeyError,
-        ElectrumError, EsploraError, ExtractTxError, PersistenceError, PsbtError, PsbtParseError,
-        RequestBuilderError, TransactionError, TxidParseError,
+        Bip32Error, Bip39Error, CannotConnectError, CreateTxError, DescriptorError,
+        DescriptorKeyError, ElectrumError, EsploraError, ExtractTxError, MessageSignatureError,
+        PaymentUriParseError, PersistenceError, PsbtError, PsbtParseError, RequestBuilderError,
+        TransactionError, TxidParseError,
     };
     use crate::SignerError;
 
@@ -1572,6 +1649,59 @@ mod test {
         assert_eq!(format!("{}", error), "cannot include height: 42");
     }
 
+    #[test]
+    fn test_error_create_tx() {
+        let cases = vec![
+            (
+                CreateTxError::InsufficientFunds {
+                    needed: 10_000,
+                    available: 5_000,
+                },
+                "insufficient funds: needed 10000 sat, available 5000 sat",
+            ),
+            (
+                CreateTxError::FrozenUtxo {
+                    outpoint: "outpoint".to_string(),
+                },
+                "utxo outpoint is frozen and cannot be spent",
+            ),
+            (
+                CreateTxError::InvalidMaxInputs { max_inputs: 1 },
+                "max inputs must be at least 2 to consolidate utxos, got 1",
+            ),
+            (
+                CreateTxError::NotEnoughUtxosToConsolidate { available: 1 },
+                "not enough utxos worth spending to consolidate: found 1, need at least 2",
+            ),
+            (
+                CreateTxError::OpReturnDataTooLarge { length: 81 },
+                "OP_RETURN data of 81 bytes exceeds the standard limit of 80 bytes",
+            ),
+            (
+                CreateTxError::Signer {
+                    error_message: "message".to_string(),
+                },
+                "signing the transaction failed: message",
+            ),
+            (
+                CreateTxError::MissingScriptPubKey {
+                    script_pubkey: "0014".to_string(),
+                },
+                "the transaction being bumped has no output with script pubkey 0014",
+            ),
+            (
+                CreateTxError::InvalidForeignUtxo {
+                    error_message: "message".to_string(),
+                },
+                "invalid foreign utxo: message",
+            ),
+        ];
+
+        for (error, expected_message) in cases {
+            assert_eq!(error.to_string(), expected_message);
+        }
+    }
+
     #[test]
     fn test_error_descriptor() {
         let cases = vec![
@@ -1659,6 +1789,10 @@ mod test {
                 },
                 "error bip 32 related: BIP32 derivation error",
             ),
+            (
+                DescriptorKeyError::InvalidNetwork,
+                "the key is for a different network",
+            ),
         ];
 
         for (error, expected_message) in cases {
@@ -1744,7 +1878,11 @@ mod test {
                     error_message: "message".to_string(),
                 },
                 "message",
-            )
+            ),
+            (
+                ElectrumError::InvalidTimeout { timeout: 256 },
+                "timeout of 256 seconds is above the maximum of 255 seconds",
+            ),
         ];
 
         for (error, expected_message) in cases {
@@ -1808,6 +1946,10 @@ mod test {
                 EsploraError::RequestAlreadyConsumed,
                 "the request has already been consumed",
             ),
+            (
+                EsploraError::FeeEstimateNotFound { target: 6 },
+                "no fee estimate available for a confirmation target of 6 blocks",
+            ),
         ];
 
         for (error, expected_message) in cases {
@@ -1843,10 +1985,23 @@ mod test {
 
     #[test]
     fn test_error_inspect() {
-        let cases = vec![(
-            RequestBuilderError::RequestAlreadyConsumed,
-            "the request has already been consumed",
-        )];
+        let cases = vec![
+            (
+                RequestBuilderError::RequestAlreadyConsumed,
+                "the request has already been consumed",
+            ),
+            (
+                RequestBuilderError::InvalidSpkRange {
+                    from_index: 10,
+                    to_index: 5,
+                },
+                "invalid spk range: from index 10 is greater than to index 5",
+            ),
+            (
+                RequestBuilderError::SpkRangeTooLarge { max: 1000 },
+                "spk range too large: at most 1000 indices can be synced at once",
+            ),
+        ];
 
         for (error, expected_message) in cases {
             assert_eq!(error.to_string(), expected_message);
@@ -1877,6 +2032,76 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_error_message_signature() {
+        let cases = vec![
+            (
+                MessageSignatureError::AddressNotInWallet,
+                "the address does not belong to this wallet",
+            ),
+            (
+                MessageSignatureError::UnsupportedAddressType,
+                "message signatures are only supported for p2wpkh and p2tr addresses",
+            ),
+            (
+                MessageSignatureError::Signer {
+                    error_message: "missing key for signing".to_string(),
+                },
+                "signing error: missing key for signing",
+            ),
+            (
+                MessageSignatureError::IncompleteSignature,
+                "the wallet could not produce a complete signature",
+            ),
+            (
+                MessageSignatureError::InvalidSignatureEncoding {
+                    error_message: "Invalid byte 33, offset 0.".to_string(),
+                },
+                "invalid signature encoding: Invalid byte 33, offset 0.",
+            ),
+        ];
+
+        for (error, expected_message) in cases {
+            assert_eq!(error.to_string(), expected_message);
+        }
+    }
+
+    #[test]
+    fn test_error_payment_uri_parse() {
+        let cases = vec![
+            (
+                PaymentUriParseError::MissingScheme,
+                "uri does not use the bitcoin: scheme",
+            ),
+            (
+                PaymentUriParseError::MissingAddress,
+                "uri does not contain an address",
+            ),
+            (
+                PaymentUriParseError::InvalidAmount {
+                    amount: "-1".to_string(),
+                },
+                "invalid amount: -1",
+            ),
+            (
+                PaymentUriParseError::InvalidPercentEncoding {
+                    parameter: "label".to_string(),
+                },
+                "invalid percent-encoding in parameter: label",
+            ),
+            (
+                PaymentUriParseError::UnknownRequiredParameter {
+                    parameter: "req-foo".to_string(),
+                },
+                "unknown required parameter: req-foo",
+            ),
+        ];
+
+        for (error, expected_message) in cases {
+            assert_eq!(error.to_string(), expected_message);
+        }
+    }
+
     #[test]
     fn test_error_psbt() {
         let cases = vec![
@@ -2034,6 +2259,12 @@ mod test {
                 },
                 "error in psbt base64 encoding: base64 decode error",
             ),
+            (
+                PsbtParseError::HexEncoding {
+                    error_message: "odd hex string length 3".to_string(),
+                },
+                "error in psbt hex encoding: odd hex string length 3",
+            ),
         ];
 
         for (error, expected_message) in cases {
@@ -2107,6 +2338,13 @@ mod test {
                 TransactionError::UnsupportedSegwitFlag { flag: 1 },
                 "unsupported segwit version: 1",
             ),
+            (
+                TransactionError::OutputIndexOutOfRange {
+                    index: 2,
+                    outputs: 2,
+                },
+                "output index 2 is out of range for a transaction with 2 outputs",
+            ),
             (
                 TransactionError::OtherTransactionErr,
                 "other transaction error",
@@ -7,6 +7,7 @@ use crate::types::{FullScanRequest, SyncRequest};
 
 use bdk_esplora::esplora_client::{BlockingClient, Builder};
 use bdk_esplora::EsploraExt;
+use bdk_wallet::bitcoin::FeeRate as BdkFeeRate;
 use bdk_wallet::bitcoin::Transaction as BdkTransaction;
 use bdk_wallet::bitcoin::Txid;
 use bdk_wallet::chain::spk_client::FullScanRequest as BdkFullScanRequest;
@@ -15,6 +16,7 @@ use bdk_wallet::chain::spk_client::SyncRequest as BdkSyncRequest;
 use bdk_wallet::chain::spk_client::SyncResponse as BdkSyncResponse;
 use bdk_wallet::KeychainKind;
 use bdk_wallet::Update as BdkUpdate;
+use bitcoin_ffi::FeeRate;
 
 use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
@@ -23,8 +25,15 @@ use std::sync::Arc;
 pub struct EsploraClient(BlockingClient);
 
 impl EsploraClient {
-    pub fn new(url: String) -> Self {
-        let client = Builder::new(url.as_str()).build_blocking();
+    pub fn new(
+        url: String,
+        proxy: Option<String>,
+        timeout: Option<u64>,
+        headers: Option<HashMap<String, String>>,
+    ) -> Self {
+        let headers = headers.unwrap_or_default();
+        let client =
+            builder_with_options(&url, proxy.as_deref(), timeout, &headers).build_blocking();
         Self(client)
     }
 
@@ -79,11 +88,12 @@ impl EsploraClient {
         Ok(Arc::new(Update(update)))
     }
 
-    pub fn broadcast(&self, transaction: &Transaction) -> Result<(), EsploraError> {
+    pub fn broadcast(&self, transaction: &Transaction) -> Result<String, EsploraError> {
         let bdk_transaction: BdkTransaction = transaction.into();
         self.0
             .broadcast(&bdk_transaction)
-            .map_err(EsploraError::from)
+            .map_err(EsploraError::from)?;
+        Ok(bdk_transaction.compute_txid().to_string())
     }
 
     pub fn get_tx(&self, txid: String) -> Result<Option<Arc<Transaction>>, EsploraError> {
@@ -100,6 +110,19 @@ impl EsploraClient {
         self.0.get_fee_estimates().map_err(EsploraError::from)
     }
 
+    pub fn estimate_fee(&self, target: u64) -> Result<Arc<FeeRate>, EsploraError> {
+        let estimates = self.0.get_fee_estimates()?;
+        // use the estimate for the largest confirmation target that is still within `target`
+        let sat_per_vb = estimates
+            .into_iter()
+            .filter(|(estimate_target, _)| u64::from(*estimate_target) <= target)
+            .max_by_key(|(estimate_target, _)| *estimate_target)
+            .map(|(_, sat_per_vb)| sat_per_vb)
+            .ok_or(EsploraError::FeeEstimateNotFound { target })?;
+        let sat_per_kwu = (sat_per_vb * 250.0).ceil() as u64;
+        Ok(Arc::new(FeeRate(BdkFeeRate::from_sat_per_kwu(sat_per_kwu))))
+    }
+
     pub fn get_block_hash(&self, block_height: u32) -> Result<String, EsploraError> {
         self.0
             .get_block_hash(block_height)
@@ -122,3 +145,120 @@ impl EsploraClient {
             .map_err(EsploraError::from)
     }
 }
+
+fn builder_with_options(
+    url: &str,
+    proxy: Option<&str>,
+    timeout: Option<u64>,
+    headers: &HashMap<String, String>,
+) -> Builder {
+    let mut builder = headers
+        .iter()
+        .fold(Builder::new(url), |builder, (key, value)| {
+            builder.header(key, value)
+        });
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::EsploraError;
+    use crate::esplora::{builder_with_options, EsploraClient};
+
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_builder_with_headers() {
+        let headers = HashMap::from([(
+            "Authorization".to_string(),
+            "Basic dXNlcjpwYXNzd29yZA==".to_string(),
+        )]);
+        let builder =
+            builder_with_options("http://127.0.0.1:3002/regtest/api", None, None, &headers);
+
+        assert_eq!(builder.base_url, "http://127.0.0.1:3002/regtest/api");
+        assert_eq!(builder.headers, headers);
+    }
+
+    #[test]
+    fn test_builder_with_all_options() {
+        // an authenticated, self-hosted instance reached over Tor
+        let headers = HashMap::from([(
+            "Authorization".to_string(),
+            "Basic dXNlcjpwYXNzd29yZA==".to_string(),
+        )]);
+        let builder = builder_with_options(
+            "http://esploraxyz.onion/api",
+            Some("socks5h://127.0.0.1:9050"),
+            Some(30),
+            &headers,
+        );
+
+        assert_eq!(builder.proxy.as_deref(), Some("socks5h://127.0.0.1:9050"));
+        assert_eq!(builder.timeout, Some(30));
+        assert_eq!(builder.headers, headers);
+    }
+
+    #[test]
+    fn test_builder_with_proxy() {
+        let builder = builder_with_options(
+            "https://mutinynet.com/api/",
+            Some("127.0.0.1:9050"),
+            None,
+            &HashMap::new(),
+        );
+        assert_eq!(builder.proxy.as_deref(), Some("127.0.0.1:9050"));
+
+        let builder =
+            builder_with_options("https://mutinynet.com/api/", None, None, &HashMap::new());
+        assert_eq!(builder.proxy, None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_unreachable_proxy() {
+        let client = EsploraClient::new(
+            "https://mutinynet.com/api/".to_string(),
+            Some("http://127.0.0.1:1".to_string()),
+            None,
+            None,
+        );
+
+        assert!(matches!(
+            client.get_height(),
+            Err(EsploraError::Minreq { .. })
+        ));
+    }
+
+    #[test]
+    fn test_builder_with_timeout() {
+        let builder =
+            builder_with_options("https://mutinynet.com/api/", None, Some(5), &HashMap::new());
+        assert_eq!(builder.timeout, Some(5));
+
+        let builder =
+            builder_with_options("https://mutinynet.com/api/", None, None, &HashMap::new());
+        assert_eq!(builder.timeout, None);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_timeout() {
+        // 10.255.255.1 is not routable, so without a timeout the connection attempt would hang
+        let client = EsploraClient::new("http://10.255.255.1/api".to_string(), None, Some(1), None);
+
+        let start = Instant::now();
+        assert!(matches!(
+            client.get_height(),
+            Err(EsploraError::Minreq { .. })
+        ));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
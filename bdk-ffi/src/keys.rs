@@ -4,14 +4,14 @@ use bdk_wallet::bitcoin::bip32::DerivationPath as BdkDerivationPath;
 use bdk_wallet::bitcoin::key::Secp256k1;
 use bdk_wallet::bitcoin::secp256k1::rand;
 use bdk_wallet::bitcoin::secp256k1::rand::Rng;
-use bdk_wallet::bitcoin::Network;
+use bdk_wallet::bitcoin::{Network, NetworkKind, PrivateKey};
 use bdk_wallet::keys::bip39::WordCount;
 use bdk_wallet::keys::bip39::{Language, Mnemonic as BdkMnemonic};
 use bdk_wallet::keys::{
     DerivableKey, DescriptorPublicKey as BdkDescriptorPublicKey,
     DescriptorSecretKey as BdkDescriptorSecretKey, ExtendedKey, GeneratableKey, GeneratedKey,
 };
-use bdk_wallet::miniscript::descriptor::{DescriptorXKey, Wildcard};
+use bdk_wallet::miniscript::descriptor::{DescriptorXKey, SinglePriv, Wildcard};
 use bdk_wallet::miniscript::BareCtx;
 
 use std::fmt::Display;
@@ -23,28 +23,47 @@ pub(crate) struct Mnemonic(BdkMnemonic);
 
 impl Mnemonic {
     pub(crate) fn new(word_count: WordCount) -> Self {
+        Mnemonic::new_in(Language::English, word_count)
+    }
+
+    pub(crate) fn new_in(language: Language, word_count: WordCount) -> Self {
         // TODO 4: I DON'T KNOW IF THIS IS A DECENT WAY TO GENERATE ENTROPY PLEASE CONFIRM
         let mut rng = rand::thread_rng();
         let mut entropy = [0u8; 32];
         rng.fill(&mut entropy);
 
         let generated_key: GeneratedKey<_, BareCtx> =
-            BdkMnemonic::generate_with_entropy((word_count, Language::English), entropy).unwrap();
-        let mnemonic = BdkMnemonic::parse_in(Language::English, generated_key.to_string()).unwrap();
+            BdkMnemonic::generate_with_entropy((word_count, language), entropy).unwrap();
+        let mnemonic = BdkMnemonic::parse_in(language, generated_key.to_string()).unwrap();
         Mnemonic(mnemonic)
     }
 
     pub(crate) fn from_string(mnemonic: String) -> Result<Self, Bip39Error> {
-        BdkMnemonic::from_str(&mnemonic)
+        Mnemonic::from_string_in(Language::English, mnemonic)
+    }
+
+    pub(crate) fn from_string_in(language: Language, mnemonic: String) -> Result<Self, Bip39Error> {
+        BdkMnemonic::parse_in(language, mnemonic)
             .map(Mnemonic)
             .map_err(Bip39Error::from)
     }
 
     pub(crate) fn from_entropy(entropy: Vec<u8>) -> Result<Self, Bip39Error> {
-        BdkMnemonic::from_entropy(entropy.as_slice())
+        Mnemonic::from_entropy_in(Language::English, entropy)
+    }
+
+    pub(crate) fn from_entropy_in(
+        language: Language,
+        entropy: Vec<u8>,
+    ) -> Result<Self, Bip39Error> {
+        BdkMnemonic::from_entropy_in(language, entropy.as_slice())
             .map(Mnemonic)
             .map_err(Bip39Error::from)
     }
+
+    pub(crate) fn language(&self) -> Language {
+        self.0.language()
+    }
 }
 
 impl Display for Mnemonic {
@@ -67,6 +86,18 @@ impl DerivationPath {
     }
 }
 
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // always emit the canonical form with the master prefix and ' for hardened children
+        let path = self.inner_mutex.lock().unwrap();
+        write!(f, "m")?;
+        for child_number in &*path {
+            write!(f, "/{}", child_number)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct DescriptorSecretKey(pub(crate) BdkDescriptorSecretKey);
 
@@ -89,6 +120,19 @@ impl DescriptorSecretKey {
         Ok(Self(descriptor_secret_key))
     }
 
+    pub(crate) fn from_wif(wif: String, network: Network) -> Result<Self, DescriptorKeyError> {
+        let key = PrivateKey::from_wif(&wif).map_err(|e| DescriptorKeyError::Parse {
+            error_message: e.to_string(),
+        })?;
+        if key.network != NetworkKind::from(network) {
+            return Err(DescriptorKeyError::InvalidNetwork);
+        }
+        Ok(Self(BdkDescriptorSecretKey::Single(SinglePriv {
+            origin: None,
+            key,
+        })))
+    }
+
     pub(crate) fn derive(&self, path: &DerivationPath) -> Result<Arc<Self>, DescriptorKeyError> {
         let secp = Secp256k1::new();
         let descriptor_secret_key = &self.0;
@@ -235,9 +279,11 @@ impl DescriptorPublicKey {
 
 #[cfg(test)]
 mod test {
-    use crate::error::DescriptorKeyError;
+    use crate::error::{Bip32Error, Bip39Error, DescriptorKeyError};
     use crate::keys::{DerivationPath, DescriptorPublicKey, DescriptorSecretKey, Mnemonic};
+    use bdk_wallet::bitcoin::hex::DisplayHex;
     use bdk_wallet::bitcoin::Network;
+    use bdk_wallet::keys::bip39::{Language, WordCount};
     use std::sync::Arc;
 
     fn get_inner() -> DescriptorSecretKey {
@@ -343,6 +389,97 @@ mod test {
         assert_eq!(extended_dsk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/0/*");
     }
 
+    #[test]
+    fn test_derive_hardened_descriptor_secret_key() {
+        let master_dsk = get_inner();
+        let derived_dsk: &DescriptorSecretKey = &derive_dsk(&master_dsk, "m/84'/0'/0'").unwrap();
+        assert_eq!(derived_dsk.as_string(), "[d1d04177/84'/0'/0']tprv8ghv4L1ZpCqVv1w3wEoxCBQJU9AWx1PnjmhkPVcUsUDqwMuTnLLQXMFChmMZxTNfYupAFgg6B8rB6kNbrVTeZW9vwehUVLMKYjE124Zq9Lm/*");
+        // extending keeps the key and origin, appending to the derivation path instead
+        let extended_dsk: &DescriptorSecretKey = &extend_dsk(derived_dsk, "m/0").unwrap();
+        assert_eq!(extended_dsk.as_string(), "[d1d04177/84'/0'/0']tprv8ghv4L1ZpCqVv1w3wEoxCBQJU9AWx1PnjmhkPVcUsUDqwMuTnLLQXMFChmMZxTNfYupAFgg6B8rB6kNbrVTeZW9vwehUVLMKYjE124Zq9Lm/0/*");
+    }
+
+    #[test]
+    fn test_derive_and_extend_descriptor_public_key() {
+        let master_dpk: &DescriptorPublicKey = &get_inner().as_public();
+        let extended_dpk: &DescriptorPublicKey = &extend_dpk(master_dpk, "m/0/5").unwrap();
+        assert_eq!(extended_dpk.as_string(), "tpubD6NzVbkrYhZ4WywdEfYbbd62yuvqLjAZuPsNyvzCNV85JekAEMbKHWSHLF9h3j45SxewXDcLv328B1SEZrxg4iwGfmdt1pDFjZiTkGiFqGa/0/5/*");
+        let derived_dpk: &DescriptorPublicKey = &derive_dpk(master_dpk, "m/0/5").unwrap();
+        assert_eq!(derived_dpk.as_string(), "[d1d04177/0/5]tpubDAcjjorhKzjUU5bnVBF1dAtX5kYCKvN3kt4C4kXuxDBwm112SiTD9N8qGcMMvq6DPQ35ankHojmoE8rtzayja1Ap4pEeN6qyPb891BbexgD/*");
+        // the derived public key matches the public half of the derived secret key
+        let derived_dsk: &DescriptorSecretKey = &derive_dsk(&get_inner(), "m/0/5").unwrap();
+        assert_eq!(derived_dsk.as_public().as_string(), derived_dpk.as_string());
+    }
+
+    #[test]
+    fn test_derivation_path_notations() {
+        let apostrophe = DerivationPath::new("m/84'/0'/0'".to_string()).unwrap();
+        let letter_h = DerivationPath::new("84h/0h/0h".to_string()).unwrap();
+        assert_eq!(apostrophe.to_string(), "m/84'/0'/0'");
+        assert_eq!(letter_h.to_string(), "m/84'/0'/0'");
+        assert_eq!(
+            DerivationPath::new("m".to_string()).unwrap().to_string(),
+            "m"
+        );
+
+        assert!(matches!(
+            DerivationPath::new("m/84'/zero/0'".to_string()),
+            Err(Bip32Error::InvalidChildNumberFormat)
+        ));
+    }
+
+    #[test]
+    fn test_mnemonic_from_entropy() {
+        let mnemonic = Mnemonic::from_entropy(vec![0u8; 16]).unwrap();
+        assert_eq!(
+            mnemonic.to_string(),
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        );
+
+        let result = Mnemonic::from_entropy(vec![0u8; 15]);
+        assert!(matches!(
+            result,
+            Err(Bip39Error::BadEntropyBitCount { bit_count: 120 })
+        ));
+    }
+
+    #[test]
+    fn test_mnemonic_from_string() {
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let mnemonic = Mnemonic::from_string(phrase.to_string()).unwrap();
+        assert_eq!(mnemonic.to_string(), phrase);
+
+        let misspelled =
+            "legal winnr thank year wave sausage worth useful legal winner thank yellow";
+        assert!(matches!(
+            Mnemonic::from_string(misspelled.to_string()),
+            Err(Bip39Error::UnknownWord { index: 1 })
+        ));
+
+        let bad_checksum =
+            "legal winner thank year wave sausage worth useful legal winner thank you";
+        assert!(matches!(
+            Mnemonic::from_string(bad_checksum.to_string()),
+            Err(Bip39Error::InvalidChecksum)
+        ));
+    }
+
+    #[test]
+    fn test_mnemonic_in_japanese() {
+        let mnemonic = Mnemonic::new_in(Language::Japanese, WordCount::Words12);
+        assert_eq!(mnemonic.language(), Language::Japanese);
+        assert_eq!(mnemonic.0.word_count(), 12);
+
+        let parsed = Mnemonic::from_string_in(Language::Japanese, mnemonic.to_string()).unwrap();
+        assert_eq!(parsed.to_string(), mnemonic.to_string());
+        assert_eq!(parsed.language(), Language::Japanese);
+
+        assert!(matches!(
+            Mnemonic::from_string(mnemonic.to_string()),
+            Err(Bip39Error::UnknownWord { index: 0 })
+        ));
+    }
+
     #[test]
     fn test_derive_hardened_path_using_public() {
         let master_dpk = get_inner().as_public();
@@ -350,16 +487,47 @@ mod test {
         assert!(derived_dpk.is_err());
     }
 
-    // TODO 7: It appears that the to_hex() method is not available anymore.
-    //       Look into the correct way to pull the hex out of the DescriptorSecretKey.
-    //       Note: ToHex was removed in bitcoin_hashes 0.12.0
-    // #[test]
-    // fn test_retrieve_master_secret_key() {
-    //     let master_dpk = get_inner();
-    //     let master_private_key = master_dpk.secret_bytes().to_hex();
-    //     assert_eq!(
-    //         master_private_key,
-    //         "e93315d6ce401eb4db803a56232f0ed3e69b053774e6047df54f1bd00e5ea936"
-    //     )
-    // }
+    #[test]
+    fn test_retrieve_master_secret_key() {
+        let master_dsk = get_inner();
+        let master_private_key = master_dsk.secret_bytes();
+        assert_eq!(master_private_key.len(), 32);
+        assert_eq!(
+            master_private_key.to_lower_hex_string(),
+            "e93315d6ce401eb4db803a56232f0ed3e69b053774e6047df54f1bd00e5ea936"
+        );
+        // the public key keeps the origin and derivation path of the secret key it came from
+        let derived_dsk: &DescriptorSecretKey = &derive_dsk(&master_dsk, "m/0").unwrap();
+        assert_eq!(derived_dsk.as_public().as_string(), "[d1d04177/0]tpubD9oaCiP1MPmQdndm7DCD3D3QU34pWd6BbKSRedoZF1UJcNhEk3PJwkALNYkhxeTKL29oGNR7psqvT1KZydCGqUDEKXN6dVQJY2R8ooLPy8m/*");
+        assert_eq!(derived_dsk.secret_bytes().len(), 32);
+    }
+
+    #[test]
+    fn test_from_wif() {
+        let wif = "L1aW4aubDFB7yfras2S1mN3bqg9nwySY8nkoLmJebSLD5BWv3ENZ";
+        let key = DescriptorSecretKey::from_wif(wif.to_string(), Network::Bitcoin).unwrap();
+        assert_eq!(key.as_string(), wif);
+        // a single key can't be derived
+        assert!(matches!(
+            derive_dsk(&key, "m/0"),
+            Err(DescriptorKeyError::InvalidKeyType)
+        ));
+
+        // a mainnet key is rejected on testnet, and a testnet key on mainnet
+        assert!(matches!(
+            DescriptorSecretKey::from_wif(wif.to_string(), Network::Testnet),
+            Err(DescriptorKeyError::InvalidNetwork)
+        ));
+        let testnet_wif = "cVt4o7BGAig1UXywgGSmARhxMdzP5qvQsxKkSsc1XEkw3tDTQFpy";
+        assert!(DescriptorSecretKey::from_wif(testnet_wif.to_string(), Network::Signet).is_ok());
+        assert!(matches!(
+            DescriptorSecretKey::from_wif(testnet_wif.to_string(), Network::Bitcoin),
+            Err(DescriptorKeyError::InvalidNetwork)
+        ));
+
+        assert!(matches!(
+            DescriptorSecretKey::from_wif("not a wif".to_string(), Network::Bitcoin),
+            Err(DescriptorKeyError::Parse { .. })
+        ));
+    }
 }
@@ -5,16 +5,21 @@ mod error;
 mod esplora;
 mod keys;
 mod store;
+#[cfg(test)]
+mod test_utils;
 mod tx_builder;
 mod types;
 mod wallet;
 
 use crate::bitcoin::Address;
+use crate::bitcoin::AddressType;
 use crate::bitcoin::FinalizedPsbtResult;
+use crate::bitcoin::PaymentUri;
 use crate::bitcoin::Psbt;
 use crate::bitcoin::Transaction;
 use crate::bitcoin::TxIn;
 use crate::bitcoin::TxOut;
+use crate::descriptor::calculate_descriptor_checksum;
 use crate::descriptor::Descriptor;
 use crate::electrum::ElectrumClient;
 use crate::electrum::ServerFeaturesRes;
@@ -32,7 +37,9 @@ use crate::error::EsploraError;
 use crate::error::ExtractTxError;
 use crate::error::FromScriptError;
 use crate::error::LoadWithPersistError;
+use crate::error::MessageSignatureError;
 use crate::error::MiniscriptError;
+use crate::error::PaymentUriParseError;
 use crate::error::PersistenceError;
 use crate::error::PsbtError;
 use crate::error::PsbtFinalizeError;
@@ -70,12 +77,16 @@ use crate::types::SatisfiableItem;
 use crate::types::ScriptAmount;
 use crate::types::SentAndReceivedValues;
 use crate::types::SignOptions;
+use crate::types::SyncProgress;
+use crate::types::SyncProgressInspector;
 use crate::types::SyncRequest;
 use crate::types::SyncRequestBuilder;
 use crate::types::SyncScriptInspector;
 use crate::types::Tx;
+use crate::types::TxOrdering;
 use crate::types::TxStatus;
 use crate::types::Update;
+use crate::types::{chain_position_confirmations, chain_position_is_confirmed};
 use crate::wallet::Wallet;
 
 use bitcoin_ffi::Amount;
@@ -84,6 +95,7 @@ use bitcoin_ffi::Network;
 use bitcoin_ffi::OutPoint;
 use bitcoin_ffi::Script;
 
+use bdk_wallet::keys::bip39::Language;
 use bdk_wallet::keys::bip39::WordCount;
 use bdk_wallet::tx_builder::ChangeSpendPolicy;
 use bdk_wallet::ChangeSet;
@@ -1,10 +1,20 @@
 use crate::error::SqliteError;
 
-use bdk_wallet::rusqlite::Connection as BdkConnection;
+use bdk_wallet::bitcoin::{OutPoint, Txid};
+use bdk_wallet::rusqlite::types::Type;
+use bdk_wallet::rusqlite::{params, Connection as BdkConnection, Error as RusqliteError};
 
+use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 
+// The frozen utxos are not part of the bdk wallet changeset, so they are kept in their own table
+const FROZEN_UTXOS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS bdk_ffi_frozen_utxos (\
+    txid TEXT NOT NULL, \
+    vout INTEGER NOT NULL, \
+    PRIMARY KEY (txid, vout))";
+
 pub struct Connection(Mutex<BdkConnection>);
 
 impl Connection {
@@ -22,3 +32,30 @@ impl Connection {
         self.0.lock().expect("must lock")
     }
 }
+
+pub(crate) fn read_frozen_utxos(db: &BdkConnection) -> Result<HashSet<OutPoint>, RusqliteError> {
+    db.execute(FROZEN_UTXOS_SCHEMA, [])?;
+    let mut statement = db.prepare("SELECT txid, vout FROM bdk_ffi_frozen_utxos")?;
+    let rows = statement.query_map([], |row| {
+        let txid = Txid::from_str(&row.get::<_, String>(0)?)
+            .map_err(|e| RusqliteError::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?;
+        Ok(OutPoint::new(txid, row.get(1)?))
+    })?;
+    rows.collect()
+}
+
+pub(crate) fn write_frozen_utxos(
+    db: &mut BdkConnection,
+    frozen_utxos: &HashSet<OutPoint>,
+) -> Result<(), RusqliteError> {
+    let db_tx = db.transaction()?;
+    db_tx.execute(FROZEN_UTXOS_SCHEMA, [])?;
+    db_tx.execute("DELETE FROM bdk_ffi_frozen_utxos", [])?;
+    for outpoint in frozen_utxos {
+        db_tx.execute(
+            "INSERT INTO bdk_ffi_frozen_utxos (txid, vout) VALUES (?1, ?2)",
+            params![outpoint.txid.to_string(), outpoint.vout],
+        )?;
+    }
+    db_tx.commit()
+}
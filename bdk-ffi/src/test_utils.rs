@@ -0,0 +1,120 @@
+use crate::descriptor::Descriptor;
+use crate::store::Connection;
+use crate::wallet::Wallet;
+
+use bdk_wallet::bitcoin::absolute::LockTime;
+use bdk_wallet::bitcoin::transaction::Version;
+use bdk_wallet::bitcoin::Amount as BdkAmount;
+use bdk_wallet::bitcoin::Transaction as BdkTransaction;
+use bdk_wallet::bitcoin::{Network, OutPoint, ScriptBuf, TxIn, TxOut, Txid};
+use bdk_wallet::KeychainKind;
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub(crate) const TPRV: &str = "tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B";
+pub(crate) const COSIGNER_TPRV: &str = "tprv8ZgxMBicQKsPeitVUz3s6cfyCECovNP7t82FaKPa4UKqV1kssWcXgLkMDjzDbgG9GWoza4pL7z727QitfzkiwX99E1Has3T3a1MKHvYWmQZ";
+pub(crate) const TPUBS: [&str; 2] = [
+    "tpubD6NzVbkrYhZ4XJBfEJ6gt9DiVdfWJijsQTCE3jtXByW3Tk6AVGQ3vL1NNxg3SjB7QkJAuutACCQjrXD8zdZSM1ZmBENszCqy49ECEHmD6rf",
+    "tpubD6NzVbkrYhZ4YfAr3jCBRk4SpqB9L1Hh442y83njwfMaker7EqZd7fHMqyTWrfRYJ1e5t2ue6BYjW5i5yQnmwqbzY1a3kfqNxog1AFcD1aE",
+];
+
+// A testnet wallet on `descriptor(0)` and `descriptor(1)`, stored in `connection`
+pub(crate) fn create_wallet_with_connection(
+    descriptor: impl Fn(u32) -> String,
+    connection: Arc<Connection>,
+) -> Wallet {
+    let descriptor =
+        |keychain| Arc::new(Descriptor::new(descriptor(keychain), Network::Testnet).unwrap());
+    Wallet::new(descriptor(0), descriptor(1), Network::Testnet, connection).unwrap()
+}
+
+// Load the wallet on `descriptor(0)` and `descriptor(1)` back from `connection`
+pub(crate) fn load_wallet(
+    descriptor: impl Fn(u32) -> String,
+    connection: Arc<Connection>,
+) -> Wallet {
+    let descriptor =
+        |keychain| Arc::new(Descriptor::new(descriptor(keychain), Network::Testnet).unwrap());
+    Wallet::load(descriptor(0), descriptor(1), connection).unwrap()
+}
+
+// A testnet wallet on `descriptor(0)` and `descriptor(1)`, stored in memory
+pub(crate) fn create_wallet_with(descriptor: impl Fn(u32) -> String) -> Wallet {
+    create_wallet_with_connection(descriptor, Arc::new(Connection::new_in_memory().unwrap()))
+}
+
+pub(crate) fn create_wallet() -> Wallet {
+    create_wallet_with(|keychain| format!("wpkh({}/84h/1h/0h/{}/*)", TPRV, keychain))
+}
+
+pub(crate) fn create_funded_wallet(amount: u64) -> Wallet {
+    let wallet = create_wallet();
+    fund_wallet(&wallet, amount);
+    wallet
+}
+
+// A 2-of-3 multisig wallet holding the private key of one of the cosigners
+pub(crate) fn create_multisig_wallet() -> Wallet {
+    create_wallet_with(|keychain| {
+        format!(
+            "wsh(multi(2,{0}/{3}/*,{1}/{3}/*,{2}/{3}/*))",
+            COSIGNER_TPRV, TPUBS[0], TPUBS[1], keychain
+        )
+    })
+}
+
+// A wallet whose descriptors have a relative and an absolute timelocked spending path
+pub(crate) fn create_timelocked_wallet() -> Wallet {
+    create_wallet_with(|keychain| {
+        format!(
+            "wsh(thresh(2,pk({0}/{3}/*),sj:and_v(v:pk({1}/{3}/*),n:older(6)),snj:and_v(v:pk({2}/{3}/*),after(630000))))",
+            TPUBS[0], TPUBS[1], COSIGNER_TPRV, keychain
+        )
+    })
+}
+
+// Add an unconfirmed utxo to the wallet so it can be tested offline
+pub(crate) fn fund_wallet(wallet: &Wallet, amount: u64) -> BdkTransaction {
+    fund_keychain(wallet, KeychainKind::External, amount)
+}
+
+pub(crate) fn fund_keychain(
+    wallet: &Wallet,
+    keychain: KeychainKind,
+    amount: u64,
+) -> BdkTransaction {
+    let address = wallet.reveal_next_address(keychain).address;
+    // Spend a different (fake) outpoint each time so every funding transaction is unique
+    let vout = wallet.transactions().len() as u32;
+    let funding_tx = funding_tx(address.script_pubkey().0.clone(), amount, vout);
+    wallet
+        .get_wallet()
+        .apply_unconfirmed_txs([(funding_tx.clone(), 100)]);
+    funding_tx
+}
+
+// A transaction paying `amount` to `script_pubkey`, spending output `vout` of a fake transaction
+pub(crate) fn funding_tx(script_pubkey: ScriptBuf, amount: u64, vout: u32) -> BdkTransaction {
+    BdkTransaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(
+                Txid::from_str("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b")
+                    .unwrap(),
+                vout,
+            ),
+            ..Default::default()
+        }],
+        output: vec![TxOut {
+            value: BdkAmount::from_sat(amount),
+            script_pubkey,
+        }],
+    }
+}
+
+// The outpoint of the utxo a funding transaction created
+pub(crate) fn funding_outpoint(funding_tx: &BdkTransaction) -> OutPoint {
+    OutPoint::new(funding_tx.compute_txid(), 0)
+}
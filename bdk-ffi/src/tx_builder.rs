@@ -1,16 +1,20 @@
 use crate::bitcoin::Psbt;
 use crate::error::CreateTxError;
-use crate::types::{LockTime, ScriptAmount};
+use crate::types::{LockTime, ScriptAmount, TxOrdering};
 use crate::wallet::Wallet;
 
 use bitcoin_ffi::{Amount, FeeRate, Script};
 
 use bdk_wallet::bitcoin::absolute::LockTime as BdkLockTime;
 use bdk_wallet::bitcoin::amount::Amount as BdkAmount;
+use bdk_wallet::bitcoin::consensus::encode::serialize;
+use bdk_wallet::bitcoin::psbt::Input as PsbtInput;
 use bdk_wallet::bitcoin::script::PushBytesBuf;
+use bdk_wallet::bitcoin::transaction::Version;
 use bdk_wallet::bitcoin::Psbt as BdkPsbt;
 use bdk_wallet::bitcoin::ScriptBuf as BdkScriptBuf;
-use bdk_wallet::bitcoin::{OutPoint, Sequence, Txid};
+use bdk_wallet::bitcoin::Transaction as BdkTransaction;
+use bdk_wallet::bitcoin::{OutPoint, Sequence, TxIn, Txid, Weight};
 use bdk_wallet::ChangeSpendPolicy;
 use bdk_wallet::KeychainKind;
 
@@ -21,6 +25,10 @@ use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
 use std::sync::Arc;
 
+// The largest OP_RETURN payload relayed by Bitcoin Core's default policy (-datacarriersize=83
+// counts the whole script, including the OP_RETURN opcode and the push opcodes)
+const MAX_OP_RETURN_DATA_SIZE: usize = 80;
+
 #[derive(Clone)]
 pub struct TxBuilder {
     pub(crate) add_global_xpubs: bool,
@@ -41,6 +49,10 @@ pub struct TxBuilder {
     pub(crate) locktime: Option<LockTime>,
     pub(crate) allow_dust: bool,
     pub(crate) version: Option<i32>,
+    pub(crate) ordering: Option<TxOrdering>,
+    pub(crate) include_output_redeem_witness_script: bool,
+    pub(crate) only_witness_utxo: bool,
+    pub(crate) foreign_utxos: Vec<(OutPoint, Vec<u8>, u64)>,
 }
 
 impl TxBuilder {
@@ -64,6 +76,10 @@ impl TxBuilder {
             locktime: None,
             allow_dust: false,
             version: None,
+            ordering: None,
+            include_output_redeem_witness_script: false,
+            only_witness_utxo: false,
+            foreign_utxos: Vec::new(),
         }
     }
 
@@ -74,6 +90,20 @@ impl TxBuilder {
         })
     }
 
+    pub(crate) fn include_output_redeem_witness_script(&self) -> Arc<Self> {
+        Arc::new(TxBuilder {
+            include_output_redeem_witness_script: true,
+            ..self.clone()
+        })
+    }
+
+    pub(crate) fn only_witness_utxo(&self) -> Arc<Self> {
+        Arc::new(TxBuilder {
+            only_witness_utxo: true,
+            ..self.clone()
+        })
+    }
+
     pub(crate) fn add_recipient(&self, script: &Script, amount: Arc<Amount>) -> Arc<Self> {
         let mut recipients: Vec<(BdkScriptBuf, BdkAmount)> = self.recipients.clone();
         recipients.append(&mut vec![(script.0.clone(), amount.0)]);
@@ -124,6 +154,20 @@ impl TxBuilder {
         })
     }
 
+    pub(crate) fn add_foreign_utxo(
+        &self,
+        outpoint: OutPoint,
+        psbt_input: Vec<u8>,
+        satisfaction_weight: u64,
+    ) -> Arc<Self> {
+        let mut foreign_utxos = self.foreign_utxos.clone();
+        foreign_utxos.push((outpoint, psbt_input, satisfaction_weight));
+        Arc::new(TxBuilder {
+            foreign_utxos,
+            ..self.clone()
+        })
+    }
+
     pub(crate) fn policy_path(
         &self,
         policy_path: HashMap<String, Vec<u64>>,
@@ -174,12 +218,14 @@ impl TxBuilder {
     pub(crate) fn fee_rate(&self, fee_rate: &FeeRate) -> Arc<Self> {
         Arc::new(TxBuilder {
             fee_rate: Some(fee_rate.clone()),
+            fee_absolute: None,
             ..self.clone()
         })
     }
 
     pub(crate) fn fee_absolute(&self, fee_amount: Arc<Amount>) -> Arc<Self> {
         Arc::new(TxBuilder {
+            fee_rate: None,
             fee_absolute: Some(fee_amount),
             ..self.clone()
         })
@@ -241,13 +287,27 @@ impl TxBuilder {
         })
     }
 
+    pub(crate) fn ordering(&self, ordering: TxOrdering) -> Arc<Self> {
+        Arc::new(TxBuilder {
+            ordering: Some(ordering),
+            ..self.clone()
+        })
+    }
+
     pub(crate) fn finish(&self, wallet: &Arc<Wallet>) -> Result<Arc<Psbt>, CreateTxError> {
+        let frozen_utxos = wallet.list_frozen_utxos();
         // TODO: I had to change the wallet here to be mutable. Why is that now required with the 1.0 API?
         let mut wallet = wallet.get_wallet();
         let mut tx_builder = wallet.build_tx();
         if self.add_global_xpubs {
             tx_builder.add_global_xpubs();
         }
+        if self.include_output_redeem_witness_script {
+            tx_builder.include_output_redeem_witness_script();
+        }
+        if self.only_witness_utxo {
+            tx_builder.only_witness_utxo();
+        }
         for (script, amount) in &self.recipients {
             tx_builder.add_recipient(script.clone(), *amount);
         }
@@ -258,15 +318,31 @@ impl TxBuilder {
             tx_builder.policy_path(policy_path.clone(), KeychainKind::Internal);
         }
         tx_builder.change_policy(self.change_policy);
+        if let Some(frozen) = self.utxos.iter().find(|utxo| frozen_utxos.contains(utxo)) {
+            return Err(CreateTxError::FrozenUtxo {
+                outpoint: frozen.to_string(),
+            });
+        }
         if !self.utxos.is_empty() {
             tx_builder
                 .add_utxos(&self.utxos)
                 .map_err(CreateTxError::from)?;
         }
+        for (outpoint, psbt_input, satisfaction_weight) in &self.foreign_utxos {
+            let psbt_input = decode_psbt_input(*outpoint, psbt_input)?;
+            tx_builder
+                .add_foreign_utxo(*outpoint, psbt_input, Weight::from_wu(*satisfaction_weight))
+                .map_err(|e| CreateTxError::InvalidForeignUtxo {
+                    error_message: e.to_string(),
+                })?;
+        }
         if !self.unspendable.is_empty() {
             let bdk_unspendable: Vec<OutPoint> = self.unspendable.clone().into_iter().collect();
             tx_builder.unspendable(bdk_unspendable);
         }
+        for outpoint in frozen_utxos {
+            tx_builder.add_unspendable(outpoint);
+        }
         if self.manually_selected_only {
             tx_builder.manually_selected_only();
         }
@@ -286,6 +362,11 @@ impl TxBuilder {
             tx_builder.set_exact_sequence(Sequence(sequence));
         }
         if !&self.data.is_empty() {
+            if self.data.len() > MAX_OP_RETURN_DATA_SIZE {
+                return Err(CreateTxError::OpReturnDataTooLarge {
+                    length: self.data.len() as u64,
+                });
+            }
             let push_bytes = PushBytesBuf::try_from(self.data.clone())?;
             tx_builder.add_data(&push_bytes);
         }
@@ -302,6 +383,9 @@ impl TxBuilder {
         if let Some(version) = self.version {
             tx_builder.version(version);
         }
+        if let Some(ordering) = self.ordering {
+            tx_builder.ordering(ordering.into());
+        }
 
         let psbt = tx_builder.finish().map_err(CreateTxError::from)?;
 
@@ -309,6 +393,32 @@ impl TxBuilder {
     }
 }
 
+// rust-bitcoin only decodes input maps as part of a whole PSBT, so wrap the input in a minimal
+// PSBT whose unsigned transaction spends `outpoint` and nothing else
+fn decode_psbt_input(outpoint: OutPoint, psbt_input: &[u8]) -> Result<PsbtInput, CreateTxError> {
+    let unsigned_tx = BdkTransaction {
+        version: Version::TWO,
+        lock_time: BdkLockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            ..Default::default()
+        }],
+        output: vec![],
+    };
+    let mut psbt_bytes = b"psbt\xff".to_vec();
+    // the global unsigned transaction (key type 0x00), followed by the end of the global map
+    psbt_bytes.extend([0x01, 0x00]);
+    psbt_bytes.extend(serialize(&serialize(&unsigned_tx)));
+    psbt_bytes.push(0x00);
+    psbt_bytes.extend(psbt_input);
+
+    let psbt =
+        BdkPsbt::deserialize(&psbt_bytes).map_err(|e| CreateTxError::InvalidForeignUtxo {
+            error_message: e.to_string(),
+        })?;
+    Ok(psbt.inputs.into_iter().next().unwrap_or_default())
+}
+
 #[derive(Clone)]
 pub(crate) struct BumpFeeTxBuilder {
     pub(crate) txid: String,
@@ -318,6 +428,7 @@ pub(crate) struct BumpFeeTxBuilder {
     pub(crate) locktime: Option<LockTime>,
     pub(crate) allow_dust: bool,
     pub(crate) version: Option<i32>,
+    pub(crate) allow_shrinking: Option<BdkScriptBuf>,
 }
 
 impl BumpFeeTxBuilder {
@@ -330,6 +441,7 @@ impl BumpFeeTxBuilder {
             locktime: None,
             allow_dust: false,
             version: None,
+            allow_shrinking: None,
         }
     }
 
@@ -368,13 +480,55 @@ impl BumpFeeTxBuilder {
         })
     }
 
+    pub(crate) fn allow_shrinking(&self, script_pubkey: &Script) -> Arc<Self> {
+        Arc::new(BumpFeeTxBuilder {
+            allow_shrinking: Some(script_pubkey.0.clone()),
+            ..self.clone()
+        })
+    }
+
     pub(crate) fn finish(&self, wallet: &Arc<Wallet>) -> Result<Arc<Psbt>, CreateTxError> {
         let txid = Txid::from_str(self.txid.as_str()).map_err(|_| CreateTxError::UnknownUtxo {
             outpoint: self.txid.clone(),
         })?;
+        let frozen_utxos = wallet.list_frozen_utxos();
         let mut wallet = wallet.get_wallet();
+        // The output to shrink is turned into the drain output of the new transaction. Every other
+        // output of the original transaction, its change included, is kept at its original value,
+        // so the higher fee is only taken from the output to shrink
+        let shrink_recipients = match (&self.allow_shrinking, wallet.get_tx(txid)) {
+            (Some(script_pubkey), Some(original)) => {
+                let outputs = &original.tx_node.tx.output;
+                if !outputs
+                    .iter()
+                    .any(|output| &output.script_pubkey == script_pubkey)
+                {
+                    return Err(CreateTxError::MissingScriptPubKey {
+                        script_pubkey: script_pubkey.to_hex_string(),
+                    });
+                }
+                let recipients: Vec<(BdkScriptBuf, BdkAmount)> = outputs
+                    .iter()
+                    .filter(|output| &output.script_pubkey != script_pubkey)
+                    .map(|output| (output.script_pubkey.clone(), output.value))
+                    .collect();
+                Some((script_pubkey.clone(), recipients))
+            }
+            _ => None,
+        };
         let mut tx_builder = wallet.build_fee_bump(txid).map_err(CreateTxError::from)?;
         tx_builder.fee_rate(self.fee_rate.0);
+        for outpoint in frozen_utxos {
+            tx_builder.add_unspendable(outpoint);
+        }
+        if let Some((script_pubkey, recipients)) = shrink_recipients {
+            // Only the original inputs are spent, so no other utxo of the wallet ends up in the
+            // output being shrunk
+            tx_builder
+                .set_recipients(recipients)
+                .drain_to(script_pubkey)
+                .manually_selected_only();
+        }
         if let Some(sequence) = self.sequence {
             tx_builder.set_exact_sequence(Sequence(sequence));
         }
@@ -400,13 +554,30 @@ impl BumpFeeTxBuilder {
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
     use std::sync::Arc;
 
+    use bdk_wallet::bitcoin::absolute::LockTime as BdkLockTime;
+    use bdk_wallet::bitcoin::bip32::DerivationPath as BdkDerivationPath;
+    use bdk_wallet::bitcoin::consensus::encode::serialize;
+    use bdk_wallet::bitcoin::transaction::Version;
+    use bdk_wallet::bitcoin::Amount as BdkAmount;
+    use bdk_wallet::bitcoin::FeeRate as BdkFeeRate;
+    use bdk_wallet::bitcoin::ScriptBuf as BdkScriptBuf;
+    use bdk_wallet::bitcoin::Transaction as BdkTransaction;
+    use bdk_wallet::bitcoin::{OutPoint, TxIn, TxOut, Txid};
+    use bdk_wallet::KeychainKind;
     use bitcoin_ffi::Network;
 
+    use crate::test_utils::{
+        create_funded_wallet, create_timelocked_wallet, create_wallet, create_wallet_with,
+        fund_keychain, fund_wallet, funding_outpoint, TPRV,
+    };
     use crate::{
-        descriptor::Descriptor, esplora::EsploraClient, store::Connection,
-        types::FullScanScriptInspector, wallet::Wallet,
+        descriptor::Descriptor, error::CreateTxError, esplora::EsploraClient, store::Connection,
+        tx_builder::BumpFeeTxBuilder, tx_builder::TxBuilder, types::FullScanScriptInspector,
+        types::LockTime, types::ScriptAmount, types::SignOptions, types::TxOrdering,
+        wallet::Wallet,
     };
 
     struct FullScanInspector;
@@ -473,7 +644,7 @@ mod tests {
             Arc::new(Connection::new_in_memory().unwrap()),
         )
         .unwrap();
-        let client = EsploraClient::new("https://mutinynet.com/api/".to_string());
+        let client = EsploraClient::new("https://mutinynet.com/api/".to_string(), None, None, None);
         let full_scan_builder = wallet.start_full_scan();
         let full_scan_request = full_scan_builder
             .inspect_spks_for_all_keychains(Arc::new(FullScanInspector))
@@ -485,4 +656,804 @@ mod tests {
         println!("Wallet balance: {:?}", wallet.balance().total.to_sat());
         wallet
     }
+
+    #[test]
+    fn test_add_data() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .add_data(vec![42; 20])
+            .finish(&wallet)
+            .unwrap();
+
+        let psbt = psbt.0.lock().unwrap();
+        let op_return = psbt
+            .unsigned_tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey.is_op_return())
+            .expect("an OP_RETURN output");
+        assert_eq!(op_return.value, BdkAmount::ZERO);
+        assert_eq!(&op_return.script_pubkey.as_bytes()[2..], &[42; 20]);
+    }
+
+    #[test]
+    fn test_add_data_too_large() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let result = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .add_data(vec![42; 81])
+            .finish(&wallet);
+
+        assert!(matches!(
+            result,
+            Err(CreateTxError::OpReturnDataTooLarge { length: 81 })
+        ));
+    }
+
+    #[test]
+    fn test_drain_wallet() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .drain_wallet()
+            .drain_to(&address.script_pubkey())
+            .finish(&wallet)
+            .unwrap();
+
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(psbt.unsigned_tx.input.len(), 1);
+        assert_eq!(psbt.unsigned_tx.output.len(), 1);
+        let output = &psbt.unsigned_tx.output[0];
+        assert_eq!(output.script_pubkey, address.script_pubkey().0);
+        // Everything but the fee is swept to the drain script
+        let fee = psbt.fee().unwrap();
+        assert_eq!(output.value + fee, BdkAmount::from_sat(100_000));
+    }
+
+    #[test]
+    fn test_add_utxos() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 100_000);
+        let selected = funding_outpoint(&fund_wallet(&wallet, 50_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(1_000)),
+            )
+            .add_utxos(vec![selected])
+            .finish(&wallet)
+            .unwrap();
+
+        let psbt = psbt.0.lock().unwrap();
+        assert!(psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .any(|input| input.previous_output == selected));
+    }
+
+    #[test]
+    fn test_add_utxo_not_owned_by_wallet() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let unknown = OutPoint::new(
+            Txid::from_str("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b")
+                .unwrap(),
+            0,
+        );
+        let result = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(1_000)),
+            )
+            .add_utxo(unknown)
+            .finish(&wallet);
+
+        assert!(matches!(result, Err(CreateTxError::UnknownUtxo { .. })));
+    }
+
+    #[test]
+    fn test_manually_selected_only() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 100_000);
+        let selected = funding_outpoint(&fund_wallet(&wallet, 5_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let recipient = |amount: u64| {
+            TxBuilder::new()
+                .add_recipient(
+                    &address.script_pubkey(),
+                    Arc::new(bitcoin_ffi::Amount::from_sat(amount)),
+                )
+                .add_utxo(selected)
+                .manually_selected_only()
+        };
+
+        // The 100,000 sat utxo is never pulled in to cover the shortfall
+        let result = recipient(10_000).finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::InsufficientFunds { .. })
+        ));
+
+        let psbt = recipient(1_000).finish(&wallet).unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(psbt.unsigned_tx.input.len(), 1);
+        assert_eq!(psbt.unsigned_tx.input[0].previous_output, selected);
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 5_000);
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let result = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&wallet);
+
+        // the amount needed includes the fee on top of the 10,000 sat output
+        match result {
+            Err(CreateTxError::InsufficientFunds { needed, available }) => {
+                assert!(needed > 10_000);
+                assert_eq!(available, 5_000);
+            }
+            _ => panic!("expected an insufficient funds error"),
+        }
+    }
+
+    #[test]
+    fn test_unspendable() {
+        let wallet = Arc::new(create_wallet());
+        let large = funding_outpoint(&fund_wallet(&wallet, 100_000));
+        fund_wallet(&wallet, 5_000);
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let not_in_wallet = OutPoint::new(
+            Txid::from_str("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b")
+                .unwrap(),
+            0,
+        );
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(50_000)),
+        );
+
+        // Unknown outpoints are ignored
+        assert!(tx_builder
+            .unspendable(vec![not_in_wallet])
+            .finish(&wallet)
+            .is_ok());
+
+        let result = tx_builder
+            .unspendable(vec![large, not_in_wallet])
+            .finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::InsufficientFunds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_frozen_utxos() {
+        let wallet = Arc::new(create_wallet());
+        let frozen = funding_outpoint(&fund_wallet(&wallet, 100_000));
+        fund_wallet(&wallet, 5_000);
+        wallet.freeze_utxo(frozen);
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let recipient = |amount: u64| {
+            TxBuilder::new().add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(amount)),
+            )
+        };
+
+        // Frozen utxos are skipped even when a transaction sets its own unspendable list
+        let psbt = recipient(1_000)
+            .unspendable(vec![])
+            .finish(&wallet)
+            .unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert!(psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .all(|input| input.previous_output != frozen));
+        let result = recipient(50_000).finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::InsufficientFunds { .. })
+        ));
+
+        // Frozen utxos cannot be forced into a transaction either
+        let result = recipient(1_000).add_utxo(frozen).finish(&wallet);
+        assert!(matches!(result, Err(CreateTxError::FrozenUtxo { .. })));
+        let result = recipient(1_000).add_utxos(vec![frozen]).finish(&wallet);
+        assert!(matches!(result, Err(CreateTxError::FrozenUtxo { .. })));
+
+        wallet.unfreeze_utxo(frozen);
+        assert!(wallet.list_frozen_utxos().is_empty());
+        assert!(recipient(50_000).finish(&wallet).is_ok());
+        assert!(recipient(1_000).add_utxo(frozen).finish(&wallet).is_ok());
+    }
+
+    #[test]
+    fn test_fee_absolute() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+        let fee_rate = bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(5));
+        let fee_absolute = Arc::new(bitcoin_ffi::Amount::from_sat(2_000));
+        let fee = |tx_builder: Arc<TxBuilder>| tx_builder.finish(&wallet).unwrap().fee().unwrap();
+
+        assert_eq!(fee(tx_builder.fee_absolute(fee_absolute.clone())), 2_000);
+        // Whichever of fee_rate and fee_absolute is set last wins
+        assert_eq!(
+            fee(tx_builder
+                .fee_rate(&fee_rate)
+                .fee_absolute(fee_absolute.clone())),
+            2_000
+        );
+        assert_ne!(
+            fee(tx_builder.fee_absolute(fee_absolute).fee_rate(&fee_rate)),
+            2_000
+        );
+    }
+
+    #[test]
+    fn test_set_recipients() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let replaced = wallet.peek_address(KeychainKind::External, 100).address;
+        let recipients: Vec<ScriptAmount> = (10..15)
+            .map(|index| ScriptAmount {
+                script: wallet
+                    .peek_address(KeychainKind::External, index)
+                    .address
+                    .script_pubkey(),
+                amount: Arc::new(bitcoin_ffi::Amount::from_sat(1_000 * index as u64)),
+            })
+            .collect();
+        let expected: Vec<_> = recipients
+            .iter()
+            .map(|recipient| (recipient.script.0.clone(), recipient.amount.0))
+            .collect();
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &replaced.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(1_000)),
+            )
+            .set_recipients(recipients)
+            .finish(&wallet)
+            .unwrap();
+
+        let psbt = psbt.0.lock().unwrap();
+        let outputs = &psbt.unsigned_tx.output;
+        assert!(outputs.len() >= 5);
+        for (script, amount) in expected {
+            assert!(outputs
+                .iter()
+                .any(|output| output.script_pubkey == script && output.value == amount));
+        }
+        // set_recipients replaces the recipients added before it
+        assert!(!outputs
+            .iter()
+            .any(|output| output.script_pubkey == replaced.script_pubkey().0));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 5_000);
+        fund_wallet(&wallet, 5_000);
+        let recipients: Vec<ScriptAmount> = [3_000, 1_000, 2_000, 1_000]
+            .iter()
+            .enumerate()
+            .map(|(index, amount)| ScriptAmount {
+                script: wallet
+                    .peek_address(KeychainKind::External, 10 + index as u32)
+                    .address
+                    .script_pubkey(),
+                amount: Arc::new(bitcoin_ffi::Amount::from_sat(*amount)),
+            })
+            .collect();
+        let expected: Vec<_> = recipients
+            .iter()
+            .map(|recipient| recipient.amount.0)
+            .collect();
+        let tx_builder = TxBuilder::new().set_recipients(recipients);
+
+        let psbt = tx_builder
+            .ordering(TxOrdering::Untouched)
+            .finish(&wallet)
+            .unwrap();
+        let values: Vec<_> = psbt.0.lock().unwrap().unsigned_tx.output[..4]
+            .iter()
+            .map(|output| output.value)
+            .collect();
+        assert_eq!(values, expected);
+
+        let psbt = tx_builder
+            .ordering(TxOrdering::Bip69Lexicographic)
+            .finish(&wallet)
+            .unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        let inputs = &psbt.unsigned_tx.input;
+        assert_eq!(inputs.len(), 2);
+        assert!(inputs.windows(2).all(|pair| {
+            (
+                pair[0].previous_output.txid.to_string(),
+                pair[0].previous_output.vout,
+            ) <= (
+                pair[1].previous_output.txid.to_string(),
+                pair[1].previous_output.vout,
+            )
+        }));
+        let outputs = &psbt.unsigned_tx.output;
+        assert!(outputs.windows(2).all(|pair| {
+            (pair[0].value, &pair[0].script_pubkey) <= (pair[1].value, &pair[1].script_pubkey)
+        }));
+    }
+
+    #[test]
+    fn test_rbf_signalling() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+        let is_rbf = |tx_builder: Arc<TxBuilder>| {
+            tx_builder
+                .finish(&wallet)
+                .unwrap()
+                .extract_tx()
+                .unwrap()
+                .is_explicitly_rbf()
+        };
+
+        assert!(is_rbf(tx_builder.clone()));
+        assert!(is_rbf(tx_builder.set_exact_sequence(0xfffffffd)));
+        assert!(!is_rbf(tx_builder.set_exact_sequence(0xfffffffe)));
+    }
+
+    #[test]
+    fn test_bump_fee_allow_shrinking() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let recipient = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .drain_wallet()
+            .drain_to(&recipient.script_pubkey())
+            .finish(&wallet)
+            .unwrap();
+        let original_fee = psbt.fee().unwrap();
+        let original_tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+        let txid = original_tx.compute_txid();
+        wallet
+            .get_wallet()
+            .apply_unconfirmed_txs([(original_tx.clone(), 200)]);
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            10,
+        )));
+        let bump_fee_builder = BumpFeeTxBuilder::new(txid.to_string(), fee_rate);
+
+        // A sweep has no change to take the higher fee from
+        assert!(bump_fee_builder.finish(&wallet).is_err());
+
+        let psbt = bump_fee_builder
+            .allow_shrinking(&recipient.script_pubkey())
+            .finish(&wallet)
+            .unwrap();
+        assert!(psbt.fee().unwrap() > original_fee);
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(psbt.unsigned_tx.output.len(), 1);
+        assert_eq!(
+            psbt.unsigned_tx.output[0].script_pubkey,
+            recipient.script_pubkey().0
+        );
+        assert!(psbt.unsigned_tx.output[0].value < original_tx.output[0].value);
+
+        let not_in_tx = wallet.peek_address(KeychainKind::External, 11).address;
+        let result = bump_fee_builder
+            .allow_shrinking(&not_in_tx.script_pubkey())
+            .finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::MissingScriptPubKey { .. })
+        ));
+    }
+
+    #[test]
+    fn test_bump_fee_allow_shrinking_keeps_change() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let recipient = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &recipient.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&wallet)
+            .unwrap();
+        let original_fee = psbt.fee().unwrap();
+        let original_tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+        let txid = original_tx.compute_txid();
+        let change = original_tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey != recipient.script_pubkey().0)
+            .unwrap()
+            .clone();
+        wallet
+            .get_wallet()
+            .apply_unconfirmed_txs([(original_tx.clone(), 200)]);
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            10,
+        )));
+
+        let psbt = BumpFeeTxBuilder::new(txid.to_string(), fee_rate)
+            .allow_shrinking(&recipient.script_pubkey())
+            .finish(&wallet)
+            .unwrap();
+        let fee_delta = psbt.fee().unwrap() - original_fee;
+        assert!(fee_delta > 0);
+        let psbt = psbt.0.lock().unwrap();
+        let outputs = &psbt.unsigned_tx.output;
+        assert_eq!(outputs.len(), 2);
+        // The change still goes back to the wallet, untouched
+        assert!(outputs.contains(&change));
+        // and the recipient only pays for the higher fee
+        let shrunk = outputs
+            .iter()
+            .find(|output| output.script_pubkey == recipient.script_pubkey().0)
+            .unwrap();
+        assert_eq!(shrunk.value, BdkAmount::from_sat(10_000 - fee_delta));
+    }
+
+    #[test]
+    fn test_bump_fee_set_exact_sequence() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&wallet)
+            .unwrap();
+        let original_tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+        let txid = original_tx.compute_txid();
+        wallet
+            .get_wallet()
+            .apply_unconfirmed_txs([(original_tx, 200)]);
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            5,
+        )));
+
+        // A replacement signalling with an explicit sequence can itself be bumped again
+        let psbt = BumpFeeTxBuilder::new(txid.to_string(), fee_rate)
+            .set_exact_sequence(0xfffffff0)
+            .finish(&wallet)
+            .unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert!(psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .all(|input| input.sequence.0 == 0xfffffff0));
+        assert!(psbt.unsigned_tx.is_explicitly_rbf());
+    }
+
+    #[test]
+    fn test_add_global_xpubs() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+
+        let psbt = tx_builder.finish(&wallet).unwrap();
+        assert!(psbt.0.lock().unwrap().xpub.is_empty());
+
+        let psbt = tx_builder.add_global_xpubs().finish(&wallet).unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert!(!psbt.xpub.is_empty());
+        // The account xpub is recorded with its origin, so signers can verify the change output
+        let account_path = BdkDerivationPath::from_str("m/84'/1'/0'").unwrap();
+        for (_, path) in psbt.xpub.values() {
+            assert_eq!(path, &account_path);
+        }
+    }
+
+    #[test]
+    fn test_include_output_redeem_witness_script() {
+        let wallet = Arc::new(create_wallet_with(|keychain| {
+            format!("wsh(pk({}/84h/1h/0h/{}/*))", TPRV, keychain)
+        }));
+        fund_wallet(&wallet, 100_000);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+
+        let psbt = tx_builder.finish(&wallet).unwrap();
+        assert!(psbt
+            .0
+            .lock()
+            .unwrap()
+            .outputs
+            .iter()
+            .all(|output| output.witness_script.is_none()));
+
+        // Both the payment to the wallet's own address and the change output are P2WSH
+        let psbt = tx_builder
+            .include_output_redeem_witness_script()
+            .finish(&wallet)
+            .unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(psbt.outputs.len(), 2);
+        for (output, txout) in psbt.outputs.iter().zip(&psbt.unsigned_tx.output) {
+            let witness_script = output.witness_script.as_ref().unwrap();
+            assert_eq!(txout.script_pubkey, witness_script.to_p2wsh());
+        }
+    }
+
+    #[test]
+    fn test_only_witness_utxo() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+
+        let psbt = tx_builder.finish(&wallet).unwrap();
+        let input = psbt.0.lock().unwrap().inputs[0].clone();
+        assert!(input.witness_utxo.is_some());
+        assert!(input.non_witness_utxo.is_some());
+
+        let psbt = tx_builder.only_witness_utxo().finish(&wallet).unwrap();
+        let input = psbt.0.lock().unwrap().inputs[0].clone();
+        assert!(input.witness_utxo.is_some());
+        assert!(input.non_witness_utxo.is_none());
+    }
+
+    #[test]
+    fn test_add_foreign_utxo() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let foreign_tx = BdkTransaction {
+            version: Version::TWO,
+            lock_time: BdkLockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: BdkAmount::from_sat(50_000),
+                script_pubkey: BdkScriptBuf::from_hex(
+                    "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+                )
+                .unwrap(),
+            }],
+        };
+        let foreign_outpoint = OutPoint::new(foreign_tx.compute_txid(), 0);
+        // A BIP174 input map holding only the non-witness utxo (key type 0x00)
+        let mut psbt_input = vec![0x01, 0x00];
+        psbt_input.extend(serialize(&serialize(&foreign_tx)));
+        psbt_input.push(0x00);
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(120_000)),
+        );
+
+        let psbt = tx_builder
+            .add_foreign_utxo(foreign_outpoint, psbt_input, 107)
+            .finish(&wallet)
+            .unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(psbt.unsigned_tx.input.len(), 2);
+        let foreign_index = psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .position(|input| input.previous_output == foreign_outpoint)
+            .unwrap();
+        assert_eq!(
+            psbt.inputs[foreign_index].non_witness_utxo.as_ref(),
+            Some(&foreign_tx)
+        );
+
+        // An input map without any utxo
+        let result = tx_builder
+            .add_foreign_utxo(foreign_outpoint, vec![0x00], 107)
+            .finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::InvalidForeignUtxo { .. })
+        ));
+    }
+
+    #[test]
+    fn test_nlocktime_and_sequence() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+
+        let psbt = tx_builder
+            .nlocktime(LockTime::Blocks { height: 800_000 })
+            .set_exact_sequence(0xfffffffe)
+            .finish(&wallet)
+            .unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(
+            psbt.unsigned_tx.lock_time,
+            BdkLockTime::from_height(800_000).unwrap()
+        );
+        assert!(psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .all(|input| input.sequence.0 == 0xfffffffe));
+
+        let psbt = tx_builder
+            .nlocktime(LockTime::Seconds {
+                consensus_time: 1_700_000_000,
+            })
+            .finish(&wallet)
+            .unwrap();
+        assert_eq!(
+            psbt.0.lock().unwrap().unsigned_tx.lock_time,
+            BdkLockTime::from_time(1_700_000_000).unwrap()
+        );
+
+        // Timestamps below the 500,000,000 threshold would be interpreted as block heights
+        let result = tx_builder
+            .nlocktime(LockTime::Seconds {
+                consensus_time: 499_999_999,
+            })
+            .finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::LockTimeConversionError)
+        ));
+    }
+
+    #[test]
+    fn test_current_height_satisfies_after() {
+        let wallet = Arc::new(create_wallet_with(|keychain| {
+            format!("wsh(and_v(v:pk({}/{}/*),after(100)))", TPRV, keychain)
+        }));
+        fund_wallet(&wallet, 50_000);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+
+        // The wallet has not synced past genesis, so the locktime is the one the policy requires
+        let psbt = tx_builder.finish(&wallet).unwrap();
+        assert_eq!(
+            psbt.0.lock().unwrap().unsigned_tx.lock_time,
+            BdkLockTime::from_height(100).unwrap()
+        );
+
+        let psbt = tx_builder.current_height(150).finish(&wallet).unwrap();
+        assert_eq!(
+            psbt.0.lock().unwrap().unsigned_tx.lock_time,
+            BdkLockTime::from_height(150).unwrap()
+        );
+        let sign_options = SignOptions {
+            trust_witness_utxo: false,
+            assume_height: Some(150),
+            allow_all_sighashes: false,
+            try_finalize: true,
+            sign_with_tap_internal_key: true,
+            allow_grinding: true,
+        };
+        assert!(wallet.sign(psbt.clone(), Some(sign_options)).unwrap());
+        assert!(psbt.extract_tx().is_ok());
+    }
+
+    #[test]
+    fn test_policy_path_selects_spending_branch() {
+        let wallet = Arc::new(create_timelocked_wallet());
+        fund_wallet(&wallet, 100_000);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let policy_id = wallet
+            .policies(KeychainKind::External)
+            .unwrap()
+            .unwrap()
+            .id();
+        let spend_with = |items: Vec<u64>| {
+            let policy_path = vec![(policy_id.clone(), items)].into_iter().collect();
+            TxBuilder::new()
+                .add_recipient(
+                    &address.script_pubkey(),
+                    Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+                )
+                .policy_path(policy_path, KeychainKind::External)
+                .finish(&wallet)
+                .unwrap()
+        };
+
+        // The relative timelock branch sets each input's nSequence to the required CSV value
+        let psbt = spend_with(vec![0, 1]);
+        let psbt = psbt.0.lock().unwrap();
+        assert!(psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .all(|input| input.sequence.0 == 6));
+
+        // The absolute timelock branch sets the transaction's nLockTime instead
+        let psbt = spend_with(vec![0, 2]);
+        let psbt = psbt.0.lock().unwrap();
+        assert_eq!(
+            psbt.unsigned_tx.lock_time,
+            BdkLockTime::from_height(630_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_change_spend_policy() {
+        let wallet = Arc::new(create_wallet());
+        let received = funding_outpoint(&fund_keychain(&wallet, KeychainKind::External, 50_000));
+        let change = funding_outpoint(&fund_keychain(&wallet, KeychainKind::Internal, 100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+        let spent = |tx_builder: Arc<TxBuilder>| -> Vec<OutPoint> {
+            let psbt = tx_builder.finish(&wallet).unwrap();
+            let psbt = psbt.0.lock().unwrap();
+            psbt.unsigned_tx
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .collect()
+        };
+
+        assert_eq!(spent(tx_builder.do_not_spend_change()), vec![received]);
+        assert_eq!(spent(tx_builder.only_spend_change()), vec![change]);
+    }
+
+    #[test]
+    fn test_allow_dust() {
+        let wallet = Arc::new(create_funded_wallet(100_000));
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(100)),
+        );
+
+        // 100 sats is below the 294 sat dust limit of a P2WPKH output
+        let result = tx_builder.finish(&wallet);
+        assert!(matches!(
+            result,
+            Err(CreateTxError::OutputBelowDustLimit { index: 0 })
+        ));
+
+        let psbt = tx_builder.allow_dust(true).finish(&wallet).unwrap();
+        let psbt = psbt.0.lock().unwrap();
+        assert!(psbt
+            .unsigned_tx
+            .output
+            .iter()
+            .any(|output| output.value == BdkAmount::from_sat(100)));
+    }
 }
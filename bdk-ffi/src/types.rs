@@ -7,8 +7,10 @@ use bitcoin_ffi::Script;
 
 use bdk_core::bitcoin::absolute::LockTime as BdkLockTime;
 use bdk_core::spk_client::SyncItem;
+use bdk_core::spk_client::SyncProgress as BdkSyncProgress;
 
 use bdk_wallet::bitcoin::Transaction as BdkTransaction;
+use bdk_wallet::bitcoin::{TxIn as BdkTxIn, TxOut as BdkTxOut};
 use bdk_wallet::chain::spk_client::FullScanRequest as BdkFullScanRequest;
 use bdk_wallet::chain::spk_client::FullScanRequestBuilder as BdkFullScanRequestBuilder;
 use bdk_wallet::chain::spk_client::SyncRequest as BdkSyncRequest;
@@ -22,6 +24,7 @@ use bdk_wallet::descriptor::policy::{
     Satisfaction as BdkSatisfaction, SatisfiableItem as BdkSatisfiableItem,
 };
 use bdk_wallet::signer::{SignOptions as BdkSignOptions, TapLeavesOptions};
+use bdk_wallet::tx_builder::TxOrdering as BdkTxOrdering;
 use bdk_wallet::AddressInfo as BdkAddressInfo;
 use bdk_wallet::Balance as BdkBalance;
 use bdk_wallet::KeychainKind;
@@ -72,6 +75,25 @@ impl From<BdkChainPosition<BdkConfirmationBlockTime>> for ChainPosition {
     }
 }
 
+/// Whether `chain_position` is anchored in the best chain.
+pub fn chain_position_is_confirmed(chain_position: ChainPosition) -> bool {
+    matches!(chain_position, ChainPosition::Confirmed { .. })
+}
+
+/// The number of confirmations of `chain_position` with the best chain at `current_height`,
+/// counting the block it is confirmed in.
+pub fn chain_position_confirmations(chain_position: ChainPosition, current_height: u32) -> u32 {
+    match chain_position {
+        ChainPosition::Confirmed {
+            confirmation_block_time,
+            ..
+        } => current_height
+            .checked_sub(confirmation_block_time.block_id.height)
+            .map_or(0, |depth| depth + 1),
+        ChainPosition::Unconfirmed { .. } => 0,
+    }
+}
+
 #[derive(Debug)]
 pub struct ConfirmationBlockTime {
     pub block_id: BlockId,
@@ -179,6 +201,34 @@ pub trait SyncScriptInspector: Sync + Send {
     fn inspect(&self, script: Arc<Script>, total: u64);
 }
 
+// Callback for the SyncRequest, receiving the full progress of the sync
+pub trait SyncProgressInspector: Sync + Send {
+    fn inspect(&self, progress: SyncProgress);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncProgress {
+    pub spks_consumed: u64,
+    pub spks_remaining: u64,
+    pub txids_consumed: u64,
+    pub txids_remaining: u64,
+    pub outpoints_consumed: u64,
+    pub outpoints_remaining: u64,
+}
+
+impl From<BdkSyncProgress> for SyncProgress {
+    fn from(progress: BdkSyncProgress) -> Self {
+        SyncProgress {
+            spks_consumed: progress.spks_consumed as u64,
+            spks_remaining: progress.spks_remaining as u64,
+            txids_consumed: progress.txids_consumed as u64,
+            txids_remaining: progress.txids_remaining as u64,
+            outpoints_consumed: progress.outpoints_consumed as u64,
+            outpoints_remaining: progress.outpoints_remaining as u64,
+        }
+    }
+}
+
 pub struct FullScanRequestBuilder(
     pub(crate) Mutex<Option<BdkFullScanRequestBuilder<KeychainKind>>>,
 );
@@ -212,6 +262,23 @@ impl SyncRequestBuilder {
         )))))
     }
 
+    pub fn inspect_progress(
+        &self,
+        inspector: Arc<dyn SyncProgressInspector>,
+    ) -> Result<Arc<Self>, RequestBuilderError> {
+        let guard = self
+            .0
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(RequestBuilderError::RequestAlreadyConsumed)?;
+        let sync_request_builder =
+            guard.inspect(move |_, progress| inspector.inspect(SyncProgress::from(progress)));
+        Ok(Arc::new(SyncRequestBuilder(Mutex::new(Some(
+            sync_request_builder,
+        )))))
+    }
+
     pub fn build(&self) -> Result<Arc<SyncRequest>, RequestBuilderError> {
         let guard = self
             .0
@@ -558,6 +625,38 @@ impl From<SignOptions> for BdkSignOptions {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxOrdering {
+    Shuffle,
+    Untouched,
+    Bip69Lexicographic,
+}
+
+impl From<TxOrdering> for BdkTxOrdering {
+    fn from(ordering: TxOrdering) -> Self {
+        match ordering {
+            TxOrdering::Shuffle => BdkTxOrdering::Shuffle,
+            TxOrdering::Untouched => BdkTxOrdering::Untouched,
+            // bdk_wallet 1.0 no longer ships a BIP69 ordering, so it is expressed as a custom one
+            TxOrdering::Bip69Lexicographic => BdkTxOrdering::Custom {
+                input_sort: Arc::new(|a: &BdkTxIn, b: &BdkTxIn| {
+                    // BIP69 compares txids in their reversed (displayed) byte order
+                    a.previous_output
+                        .txid
+                        .to_string()
+                        .cmp(&b.previous_output.txid.to_string())
+                        .then(a.previous_output.vout.cmp(&b.previous_output.vout))
+                }),
+                output_sort: Arc::new(|a: &BdkTxOut, b: &BdkTxOut| {
+                    a.value
+                        .cmp(&b.value)
+                        .then_with(|| a.script_pubkey.cmp(&b.script_pubkey))
+                }),
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TxStatus {
     pub confirmed: bool,
@@ -601,3 +700,40 @@ impl From<BdkTx> for Tx {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{
+        chain_position_confirmations, chain_position_is_confirmed, BlockId, ChainPosition,
+        ConfirmationBlockTime,
+    };
+
+    fn confirmed_at(height: u32) -> ChainPosition {
+        ChainPosition::Confirmed {
+            confirmation_block_time: ConfirmationBlockTime {
+                block_id: BlockId {
+                    height,
+                    hash: "0000000000000000000000000000000000000000000000000000000000000000"
+                        .to_string(),
+                },
+                confirmation_time: 1_700_000_000,
+            },
+            transitively: None,
+        }
+    }
+
+    #[test]
+    fn test_chain_position_confirmations() {
+        assert!(chain_position_is_confirmed(confirmed_at(100)));
+        assert_eq!(chain_position_confirmations(confirmed_at(100), 105), 6);
+        assert_eq!(chain_position_confirmations(confirmed_at(100), 100), 1);
+        // a tip below the confirmation height (e.g. a stale local chain) saturates at 0
+        assert_eq!(chain_position_confirmations(confirmed_at(100), 99), 0);
+
+        let pending = || ChainPosition::Unconfirmed {
+            timestamp: Some(1_700_000_000),
+        };
+        assert!(!chain_position_is_confirmed(pending()));
+        assert_eq!(chain_position_confirmations(pending(), 105), 0);
+    }
+}
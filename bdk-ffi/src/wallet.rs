@@ -1,28 +1,56 @@
-use crate::bitcoin::{Psbt, Transaction};
+use crate::bitcoin::{bip322_to_sign, bip322_to_spend, Address, Psbt, Transaction};
 use crate::descriptor::Descriptor;
 use crate::error::{
-    CalculateFeeError, CannotConnectError, CreateWithPersistError, DescriptorError,
-    LoadWithPersistError, SignerError, SqliteError, TxidParseError,
+    CalculateFeeError, CannotConnectError, CreateTxError, CreateWithPersistError, DescriptorError,
+    LoadWithPersistError, MessageSignatureError, RequestBuilderError, SignerError, SqliteError,
+    TxidParseError,
 };
-use crate::store::Connection;
+use crate::store::{read_frozen_utxos, write_frozen_utxos, Connection};
 use crate::types::{
     AddressInfo, Balance, CanonicalTx, FullScanRequestBuilder, KeychainAndIndex, LocalOutput,
-    Policy, SentAndReceivedValues, SignOptions, SyncRequestBuilder, Update,
+    Policy, ScriptAmount, SentAndReceivedValues, SignOptions, SyncRequestBuilder, Update,
 };
 
 use bitcoin_ffi::{Amount, FeeRate, OutPoint, Script};
 
-use bdk_wallet::bitcoin::{Network, Txid};
+use bdk_wallet::bitcoin::base64::engine::general_purpose::STANDARD;
+use bdk_wallet::bitcoin::base64::Engine;
+use bdk_wallet::bitcoin::consensus::encode::serialize;
+use bdk_wallet::bitcoin::secp256k1::Secp256k1;
+use bdk_wallet::bitcoin::{
+    Amount as BdkAmount, Network, Psbt as BdkPsbt, ScriptBuf as BdkScriptBuf,
+    Transaction as BdkTransaction, TxIn, Txid,
+};
+use bdk_wallet::chain::spk_client::FullScanRequest as BdkFullScanRequest;
+use bdk_wallet::chain::spk_client::SyncRequest as BdkSyncRequest;
+use bdk_wallet::chain::ChainPosition as BdkChainPosition;
 use bdk_wallet::rusqlite::Connection as BdkConnection;
 use bdk_wallet::signer::SignOptions as BdkSignOptions;
-use bdk_wallet::{KeychainKind, PersistedWallet, Wallet as BdkWallet};
+use bdk_wallet::signer::SignerId;
+use bdk_wallet::{
+    KeychainKind, LocalOutput as BdkLocalOutput, PersistedWallet, Wallet as BdkWallet,
+};
 
 use std::borrow::BorrowMut;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex, MutexGuard};
 
 pub struct Wallet {
     inner_mutex: Mutex<PersistedWallet<BdkConnection>>,
+    frozen_utxos: Mutex<FrozenUtxos>,
+    // The script pubkeys of the last `start_sync_with_spk_range` request and their derivation
+    spk_range: Mutex<HashMap<BdkScriptBuf, (KeychainKind, u32)>>,
+}
+
+// The largest number of derivation indices per keychain `start_sync_with_spk_range` accepts
+const MAX_SPK_RANGE: u32 = 1_000;
+
+#[derive(Default)]
+struct FrozenUtxos {
+    outpoints: HashSet<OutPoint>,
+    // Whether the set changed since it was last persisted
+    changed: bool,
 }
 
 impl Wallet {
@@ -44,6 +72,8 @@ impl Wallet {
 
         Ok(Wallet {
             inner_mutex: Mutex::new(wallet),
+            frozen_utxos: Mutex::new(FrozenUtxos::default()),
+            spk_range: Mutex::new(HashMap::new()),
         })
     }
 
@@ -63,9 +93,17 @@ impl Wallet {
             .extract_keys()
             .load_wallet(db)?
             .ok_or(LoadWithPersistError::CouldNotLoad)?;
+        let frozen_utxos = read_frozen_utxos(db).map_err(|e| LoadWithPersistError::Persist {
+            error_message: e.to_string(),
+        })?;
 
         Ok(Wallet {
             inner_mutex: Mutex::new(wallet),
+            frozen_utxos: Mutex::new(FrozenUtxos {
+                outpoints: frozen_utxos,
+                changed: false,
+            }),
+            spk_range: Mutex::new(HashMap::new()),
         })
     }
 
@@ -73,6 +111,32 @@ impl Wallet {
         self.inner_mutex.lock().expect("wallet")
     }
 
+    fn get_frozen_utxos(&self) -> MutexGuard<FrozenUtxos> {
+        self.frozen_utxos.lock().expect("frozen utxos")
+    }
+
+    fn get_spk_range(&self) -> MutexGuard<HashMap<BdkScriptBuf, (KeychainKind, u32)>> {
+        self.spk_range.lock().expect("spk range")
+    }
+
+    pub fn freeze_utxo(&self, outpoint: OutPoint) {
+        let mut frozen_utxos = self.get_frozen_utxos();
+        if frozen_utxos.outpoints.insert(outpoint) {
+            frozen_utxos.changed = true;
+        }
+    }
+
+    pub fn unfreeze_utxo(&self, outpoint: OutPoint) {
+        let mut frozen_utxos = self.get_frozen_utxos();
+        if frozen_utxos.outpoints.remove(&outpoint) {
+            frozen_utxos.changed = true;
+        }
+    }
+
+    pub fn list_frozen_utxos(&self) -> Vec<OutPoint> {
+        self.get_frozen_utxos().outpoints.iter().copied().collect()
+    }
+
     pub fn derivation_of_spk(&self, spk: Arc<Script>) -> Option<KeychainAndIndex> {
         self.get_wallet()
             .derivation_of_spk(spk.0.clone())
@@ -126,8 +190,39 @@ impl Wallet {
             .collect()
     }
 
+    pub fn insert_tx(&self, tx: &Transaction, last_seen: u64) -> bool {
+        let tx: BdkTransaction = tx.into();
+        let mut wallet = self.get_wallet();
+        let inserted = wallet.get_tx(tx.compute_txid()).is_none();
+        // an unanchored transaction is only canonical once it has been seen in the mempool
+        wallet.apply_unconfirmed_txs([(tx, last_seen)]);
+        inserted
+    }
+
     pub fn apply_update(&self, update: Arc<Update>) -> Result<(), CannotConnectError> {
-        self.get_wallet()
+        let mut wallet = self.get_wallet();
+
+        // Reveal the addresses of the last spk range requested up to the highest index the
+        // update found a transaction for, otherwise the wallet would not index those outputs
+        let mut last_found: BTreeMap<KeychainKind, u32> = BTreeMap::new();
+        let spk_range = self.get_spk_range();
+        let found = update
+            .0
+            .tx_update
+            .txs
+            .iter()
+            .flat_map(|tx| tx.output.iter())
+            .filter_map(|txout| spk_range.get(&txout.script_pubkey));
+        for &(keychain, index) in found {
+            let last = last_found.entry(keychain).or_insert(index);
+            *last = (*last).max(index);
+        }
+        drop(spk_range);
+        for (keychain, index) in last_found {
+            wallet.reveal_addresses_to(keychain, index).for_each(drop);
+        }
+
+        wallet
             .apply_update(update.0.clone())
             .map_err(CannotConnectError::from)
     }
@@ -140,6 +235,28 @@ impl Wallet {
         self.get_wallet().descriptor_checksum(keychain)
     }
 
+    pub fn get_descriptor_for_keychain(&self, keychain: KeychainKind) -> Arc<Descriptor> {
+        let wallet = self.get_wallet();
+        let secp = Secp256k1::new();
+        Arc::new(Descriptor {
+            extended_descriptor: wallet.public_descriptor(keychain).clone(),
+            key_map: wallet.get_signers(keychain).as_key_map(&secp),
+        })
+    }
+
+    pub fn get_signers(&self, keychain: KeychainKind) -> Vec<String> {
+        self.get_wallet()
+            .get_signers(keychain)
+            .ids()
+            .into_iter()
+            .map(|id| match id {
+                SignerId::PkHash(hash) => hash.to_string(),
+                SignerId::Fingerprint(fingerprint) => fingerprint.to_string(),
+                SignerId::Dummy(id) => id.to_string(),
+            })
+            .collect()
+    }
+
     pub fn policies(&self, keychain: KeychainKind) -> Result<Option<Arc<Policy>>, DescriptorError> {
         self.get_wallet()
             .policies(keychain)
@@ -156,6 +273,19 @@ impl Wallet {
         Balance::from(bdk_balance)
     }
 
+    pub fn balance_with_policy(&self, include_untrusted_pending: bool) -> Balance {
+        let bdk_balance = self.get_wallet().balance();
+        let mut trusted_spendable = bdk_balance.trusted_spendable();
+        if include_untrusted_pending {
+            trusted_spendable += bdk_balance.untrusted_pending;
+        }
+        // the components are reported as they are, only the spendable amount follows the policy
+        Balance {
+            trusted_spendable: Arc::new(trusted_spendable.into()),
+            ..Balance::from(bdk_balance)
+        }
+    }
+
     pub fn is_mine(&self, script: Arc<Script>) -> bool {
         self.get_wallet().is_mine(script.0.clone())
     }
@@ -176,6 +306,141 @@ impl Wallet {
             .map_err(SignerError::from)
     }
 
+    pub fn create_signed_transaction(
+        &self,
+        recipients: Vec<ScriptAmount>,
+        fee_rate: Arc<FeeRate>,
+        sign_options: Option<SignOptions>,
+    ) -> Result<Arc<Psbt>, CreateTxError> {
+        let frozen_utxos = self.list_frozen_utxos();
+        let mut wallet = self.get_wallet();
+        let mut tx_builder = wallet.build_tx();
+        for recipient in &recipients {
+            tx_builder.add_recipient(recipient.script.0.clone(), recipient.amount.0);
+        }
+        for outpoint in frozen_utxos {
+            tx_builder.add_unspendable(outpoint);
+        }
+        tx_builder.fee_rate(fee_rate.0);
+        let mut psbt: BdkPsbt = tx_builder.finish()?;
+
+        let bdk_sign_options: BdkSignOptions = match sign_options {
+            Some(sign_options) => BdkSignOptions::from(sign_options),
+            None => BdkSignOptions::default(),
+        };
+        wallet
+            .sign(&mut psbt, bdk_sign_options)
+            .map_err(|e| CreateTxError::Signer {
+                error_message: e.to_string(),
+            })?;
+
+        Ok(Arc::new(psbt.into()))
+    }
+
+    pub fn build_consolidation_tx(
+        &self,
+        fee_rate: Arc<FeeRate>,
+        max_inputs: Option<u32>,
+    ) -> Result<Arc<Psbt>, CreateTxError> {
+        if let Some(max_inputs @ (0 | 1)) = max_inputs {
+            return Err(CreateTxError::InvalidMaxInputs { max_inputs });
+        }
+        let frozen_utxos = self.list_frozen_utxos();
+        let mut wallet = self.get_wallet();
+
+        // A utxo worth less than the fee of spending it would only make the consolidation smaller
+        let mut input_fees = BTreeMap::new();
+        for keychain in [KeychainKind::External, KeychainKind::Internal] {
+            let satisfaction_weight = wallet
+                .public_descriptor(keychain)
+                .max_weight_to_satisfy()
+                .map_err(|e| CreateTxError::Descriptor {
+                    error_message: e.to_string(),
+                })?;
+            let input_fee = fee_rate
+                .0
+                .fee_wu(TxIn::default().segwit_weight() + satisfaction_weight)
+                .unwrap_or(BdkAmount::MAX_MONEY);
+            input_fees.insert(keychain, input_fee);
+        }
+        let mut utxos: Vec<BdkLocalOutput> = wallet
+            .list_unspent()
+            .filter(|utxo| !frozen_utxos.contains(&utxo.outpoint))
+            .filter(|utxo| utxo.txout.value > input_fees[&utxo.keychain])
+            .collect();
+        if utxos.len() < 2 {
+            return Err(CreateTxError::NotEnoughUtxosToConsolidate {
+                available: utxos.len() as u32,
+            });
+        }
+        utxos.sort_by_key(|utxo| utxo.txout.value);
+        if let Some(max_inputs) = max_inputs {
+            utxos.truncate(max_inputs as usize);
+        }
+        let outpoints: Vec<OutPoint> = utxos.iter().map(|utxo| utxo.outpoint).collect();
+        let drain_script = wallet
+            .next_unused_address(KeychainKind::Internal)
+            .script_pubkey();
+
+        let mut tx_builder = wallet.build_tx();
+        tx_builder
+            .add_utxos(&outpoints)
+            .map_err(CreateTxError::from)?
+            .manually_selected_only()
+            .drain_to(drain_script)
+            .fee_rate(fee_rate.0);
+        let psbt: BdkPsbt = tx_builder.finish()?;
+
+        Ok(Arc::new(psbt.into()))
+    }
+
+    pub fn sign_message(
+        &self,
+        message: String,
+        address: Arc<Address>,
+    ) -> Result<String, MessageSignatureError> {
+        let script_pubkey = address.script_pubkey().0.clone();
+        if !script_pubkey.is_p2wpkh() && !script_pubkey.is_p2tr() {
+            return Err(MessageSignatureError::UnsupportedAddressType);
+        }
+
+        let wallet = self.get_wallet();
+        let (keychain, derivation_index) = wallet
+            .derivation_of_spk(script_pubkey.clone())
+            .ok_or(MessageSignatureError::AddressNotInWallet)?;
+
+        // BIP322 simple signatures are the witness of a virtual transaction spending an output locked
+        // to the address, which the wallet can sign like any of its own utxos
+        let to_spend = bip322_to_spend(&script_pubkey, &message);
+        let to_sign = bip322_to_sign(&to_spend);
+        let utxo = BdkLocalOutput {
+            outpoint: to_sign.input[0].previous_output,
+            txout: to_spend.output[0].clone(),
+            keychain,
+            is_spent: false,
+            derivation_index,
+            chain_position: BdkChainPosition::Unconfirmed { last_seen: None },
+        };
+        let signer_error = |error_message: String| MessageSignatureError::Signer { error_message };
+        let mut psbt =
+            BdkPsbt::from_unsigned_tx(to_sign).map_err(|e| signer_error(e.to_string()))?;
+        psbt.inputs[0] = wallet
+            .get_psbt_input(utxo, None, true)
+            .map_err(|e| signer_error(e.to_string()))?;
+
+        let sign_options = BdkSignOptions {
+            trust_witness_utxo: true,
+            ..BdkSignOptions::default()
+        };
+        let finalized = wallet
+            .sign(&mut psbt, sign_options)
+            .map_err(|e| signer_error(e.to_string()))?;
+        match psbt.inputs[0].final_script_witness.take() {
+            Some(witness) if finalized => Ok(STANDARD.encode(serialize(&witness))),
+            _ => Err(MessageSignatureError::IncompleteSignature),
+        }
+    }
+
     pub fn finalize_psbt(
         &self,
         psbt: Arc<Psbt>,
@@ -242,19 +507,880 @@ impl Wallet {
         Arc::new(FullScanRequestBuilder(Mutex::new(Some(builder))))
     }
 
+    pub fn start_full_scan_for_keychain(
+        &self,
+        keychain: KeychainKind,
+    ) -> Arc<FullScanRequestBuilder> {
+        let wallet = self.get_wallet();
+        let builder = BdkFullScanRequest::builder()
+            .chain_tip(wallet.local_chain().tip())
+            .spks_for_keychain(keychain, wallet.unbounded_spk_iter(keychain));
+        Arc::new(FullScanRequestBuilder(Mutex::new(Some(builder))))
+    }
+
     pub fn start_sync_with_revealed_spks(&self) -> Arc<SyncRequestBuilder> {
         let builder = self.get_wallet().start_sync_with_revealed_spks();
         Arc::new(SyncRequestBuilder(Mutex::new(Some(builder))))
     }
 
+    pub fn start_sync_with_spk_range(
+        &self,
+        from_index: u32,
+        to_index: u32,
+    ) -> Result<Arc<SyncRequestBuilder>, RequestBuilderError> {
+        if from_index > to_index {
+            return Err(RequestBuilderError::InvalidSpkRange {
+                from_index,
+                to_index,
+            });
+        }
+        if to_index - from_index >= MAX_SPK_RANGE {
+            return Err(RequestBuilderError::SpkRangeTooLarge { max: MAX_SPK_RANGE });
+        }
+
+        let wallet = self.get_wallet();
+        // the range is only revealed once an update finds transactions for it, see `apply_update`
+        let mut spk_range = self.get_spk_range();
+        spk_range.clear();
+        let mut spks = Vec::new();
+        for keychain in [KeychainKind::External, KeychainKind::Internal] {
+            for index in from_index..=to_index {
+                let script_pubkey = wallet.peek_address(keychain, index).script_pubkey();
+                spk_range.insert(script_pubkey.clone(), (keychain, index));
+                spks.push(((keychain, index), script_pubkey));
+            }
+        }
+        let builder = BdkSyncRequest::builder()
+            .chain_tip(wallet.local_chain().tip())
+            .spks_with_indexes(spks);
+        Ok(Arc::new(SyncRequestBuilder(Mutex::new(Some(builder)))))
+    }
+
     // pub fn persist(&self, connection: Connection) -> Result<bool, FfiGenericError> {
     pub fn persist(&self, connection: Arc<Connection>) -> Result<bool, SqliteError> {
         let mut binding = connection.get_store();
         let db: &mut BdkConnection = binding.borrow_mut();
-        self.get_wallet()
+        let persisted = self
+            .get_wallet()
             .persist(db)
             .map_err(|e| SqliteError::Sqlite {
                 rusqlite_error: e.to_string(),
+            })?;
+
+        let mut frozen_utxos = self.get_frozen_utxos();
+        if !frozen_utxos.changed {
+            return Ok(persisted);
+        }
+        write_frozen_utxos(db, &frozen_utxos.outpoints)?;
+        frozen_utxos.changed = false;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitcoin::{Address, Psbt, Transaction};
+    use crate::descriptor::Descriptor;
+    use crate::error::{
+        CreateTxError, MessageSignatureError, RequestBuilderError, SignerError, TxidParseError,
+    };
+    use crate::keys::DescriptorSecretKey;
+    use crate::store::Connection;
+    use crate::test_utils::{
+        create_multisig_wallet, create_wallet, create_wallet_with, create_wallet_with_connection,
+        fund_wallet, funding_outpoint, funding_tx, load_wallet, COSIGNER_TPRV, TPRV, TPUBS,
+    };
+    use crate::tx_builder::TxBuilder;
+    use crate::types::{
+        ChainPosition, SatisfiableItem, ScriptAmount, SignOptions, SyncProgress,
+        SyncProgressInspector, Update,
+    };
+    use crate::wallet::{Wallet, MAX_SPK_RANGE};
+
+    use bdk_core::TxUpdate;
+    use bdk_wallet::bitcoin::base64::engine::general_purpose::STANDARD;
+    use bdk_wallet::bitcoin::base64::Engine;
+    use bdk_wallet::bitcoin::consensus::encode::deserialize;
+    use bdk_wallet::bitcoin::sighash::EcdsaSighashType;
+    use bdk_wallet::bitcoin::FeeRate as BdkFeeRate;
+    use bdk_wallet::bitcoin::{Network, OutPoint, Witness};
+    use bdk_wallet::serde_json;
+    use bdk_wallet::KeychainKind;
+    use bdk_wallet::Update as BdkUpdate;
+
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_get_signers() {
+        // signers are identified by the fingerprint of their master key
+        let wallet = create_wallet();
+        assert_eq!(wallet.get_signers(KeychainKind::External), vec!["9122d9e0"]);
+        assert_eq!(wallet.get_signers(KeychainKind::Internal), vec!["9122d9e0"]);
+
+        // only one of the three cosigners' private keys is known
+        let wallet = create_multisig_wallet();
+        assert_eq!(wallet.get_signers(KeychainKind::External).len(), 1);
+
+        let watch_only =
+            create_wallet_with(|keychain| format!("wpkh({}/{}/*)", TPUBS[0], keychain));
+        assert!(watch_only.get_signers(KeychainKind::External).is_empty());
+    }
+
+    #[test]
+    fn test_policies() {
+        let wallet = create_multisig_wallet();
+        let policy = wallet.policies(KeychainKind::External).unwrap().unwrap();
+
+        assert!(matches!(
+            policy.item(),
+            SatisfiableItem::Multisig { ref keys, threshold: 2 } if keys.len() == 3
+        ));
+
+        let json: serde_json::Value = serde_json::from_str(&policy.as_string()).unwrap();
+        assert_eq!(json["id"], policy.id());
+        assert_eq!(json["type"], "MULTISIG");
+        assert_eq!(json["threshold"], 2);
+        assert!(json.get("satisfaction").is_some());
+        assert!(json.get("contribution").is_some());
+    }
+
+    #[test]
+    fn test_list_unspent() {
+        let wallet = create_wallet();
+        assert!(wallet.list_unspent().is_empty());
+
+        fund_wallet(&wallet, 50_000);
+        fund_wallet(&wallet, 25_000);
+        let unspent = wallet.list_unspent();
+        assert_eq!(unspent.len(), 2);
+
+        let mut values: Vec<u64> = unspent.iter().map(|utxo| utxo.txout.value).collect();
+        values.sort();
+        assert_eq!(values, vec![25_000, 50_000]);
+        for utxo in unspent {
+            assert!(!utxo.is_spent);
+            assert_eq!(utxo.keychain, KeychainKind::External);
+            assert!(Address::from_script(utxo.txout.script_pubkey, Network::Testnet).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_get_utxo() {
+        let wallet = create_wallet();
+        let funding_tx = fund_wallet(&wallet, 50_000);
+        let outpoint = funding_outpoint(&funding_tx);
+
+        let utxo = wallet.get_utxo(outpoint).unwrap();
+        assert_eq!(utxo.outpoint.txid, funding_tx.compute_txid());
+        assert_eq!(utxo.outpoint.vout, 0);
+        assert_eq!(utxo.txout.value, 50_000);
+        assert!(wallet
+            .get_utxo(OutPoint::new(funding_tx.compute_txid(), 1))
+            .is_none());
+    }
+
+    #[test]
+    fn test_transactions() {
+        let wallet = create_wallet();
+        let first = fund_wallet(&wallet, 50_000).compute_txid().to_string();
+        let second = fund_wallet(&wallet, 25_000).compute_txid().to_string();
+
+        let transactions = wallet.transactions();
+        assert_eq!(transactions.len(), 2);
+        let mut txids: Vec<String> = transactions
+            .iter()
+            .map(|tx| tx.transaction.compute_txid())
+            .collect();
+        txids.sort();
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(txids, expected);
+        for tx in transactions {
+            assert!(matches!(
+                tx.chain_position,
+                ChainPosition::Unconfirmed {
+                    timestamp: Some(100)
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_tx() {
+        let wallet = create_wallet();
+        let txid = fund_wallet(&wallet, 50_000).compute_txid().to_string();
+
+        let tx = wallet.get_tx(txid.clone()).unwrap().unwrap();
+        assert_eq!(tx.transaction.compute_txid(), txid);
+
+        let unknown = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+        assert!(wallet.get_tx(unknown.to_string()).unwrap().is_none());
+
+        assert!(matches!(
+            wallet.get_tx("not a txid".to_string()),
+            Err(TxidParseError::InvalidTxid { txid }) if txid == "not a txid"
+        ));
+    }
+
+    #[test]
+    fn test_is_mine() {
+        let wallet = create_wallet();
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+        assert!(wallet.is_mine(address.script_pubkey()));
+
+        let foreign = Address::new(
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".to_string(),
+            Network::Testnet,
+        )
+        .unwrap();
+        assert!(!wallet.is_mine(foreign.script_pubkey()));
+    }
+
+    #[test]
+    fn test_internal_addresses() {
+        let wallet = create_wallet();
+        for index in 0..3 {
+            let external = wallet.peek_address(KeychainKind::External, index);
+            let internal = wallet.peek_address(KeychainKind::Internal, index);
+            assert_eq!(internal.keychain, KeychainKind::Internal);
+            assert_eq!(internal.index, index);
+            assert_ne!(internal.address.to_string(), external.address.to_string());
+        }
+
+        let revealed = wallet.reveal_next_address(KeychainKind::Internal);
+        assert_eq!(revealed.keychain, KeychainKind::Internal);
+        assert_eq!(
+            revealed.address.to_string(),
+            wallet
+                .peek_address(KeychainKind::Internal, revealed.index)
+                .address
+                .to_string()
+        );
+        assert!(wallet.is_mine(revealed.address.script_pubkey()));
+    }
+
+    #[test]
+    fn test_get_descriptor_for_keychain() {
+        let descriptor = Descriptor::new("wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/0h/0/*)".to_string(), Network::Testnet).unwrap();
+        let change_descriptor = Descriptor::new("wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/0h/1/*)".to_string(), Network::Testnet).unwrap();
+        let wallet = create_wallet();
+
+        let external = wallet.get_descriptor_for_keychain(KeychainKind::External);
+        assert_eq!(external.to_string(), descriptor.to_string());
+        assert_eq!(
+            external.to_string_with_secret(),
+            descriptor.to_string_with_secret()
+        );
+        assert!(external.to_string().ends_with(&format!(
+            "#{}",
+            wallet.descriptor_checksum(KeychainKind::External)
+        )));
+
+        let internal = wallet.get_descriptor_for_keychain(KeychainKind::Internal);
+        assert_eq!(
+            internal.to_string_with_secret(),
+            change_descriptor.to_string_with_secret()
+        );
+    }
+
+    #[test]
+    fn test_sign_without_finalizing() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 50_000);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&wallet)
+            .unwrap();
+
+        let sign_options = SignOptions {
+            trust_witness_utxo: false,
+            assume_height: None,
+            allow_all_sighashes: false,
+            try_finalize: false,
+            sign_with_tap_internal_key: true,
+            allow_grinding: true,
+        };
+        let finalized = wallet.sign(psbt.clone(), Some(sign_options)).unwrap();
+        assert!(!finalized);
+        {
+            let psbt = psbt.0.lock().unwrap();
+            assert_eq!(psbt.inputs[0].partial_sigs.len(), 1);
+            assert!(psbt.inputs[0].final_script_witness.is_none());
+        }
+
+        // The partial signature can be finalized later, e.g. once every cosigner has signed
+        let finalized = wallet.finalize_psbt(psbt.clone(), None).unwrap();
+        assert!(finalized);
+        let psbt = psbt.0.lock().unwrap();
+        assert!(psbt.inputs[0].final_script_witness.is_some());
+    }
+
+    #[test]
+    fn test_sign_reports_finalized() {
+        let sign = |wallet: Wallet| {
+            let wallet = Arc::new(wallet);
+            fund_wallet(&wallet, 50_000);
+            let address = wallet.peek_address(KeychainKind::External, 10).address;
+            let psbt = TxBuilder::new()
+                .add_recipient(
+                    &address.script_pubkey(),
+                    Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+                )
+                .finish(&wallet)
+                .unwrap();
+            let finalized = wallet.sign(psbt.clone(), None).unwrap();
+            let partial_sigs = psbt.0.lock().unwrap().inputs[0].partial_sigs.len();
+            (finalized, partial_sigs)
+        };
+
+        // A single signer of a 2-of-3 multisig only adds its partial signature
+        assert_eq!(sign(create_multisig_wallet()), (false, 1));
+        // Finalizing clears the partial signatures of a single-sig input
+        assert_eq!(sign(create_wallet()), (true, 0));
+    }
+
+    #[test]
+    fn test_sign_with_non_default_sighash() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 50_000);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&wallet)
+            .unwrap();
+        psbt.0.lock().unwrap().inputs[0].sighash_type = Some(EcdsaSighashType::Single.into());
+
+        let result = wallet.sign(psbt.clone(), None);
+        assert!(matches!(result, Err(SignerError::NonStandardSighash)));
+
+        let sign_options = SignOptions {
+            trust_witness_utxo: false,
+            assume_height: None,
+            allow_all_sighashes: true,
+            try_finalize: true,
+            sign_with_tap_internal_key: true,
+            allow_grinding: true,
+        };
+        assert!(wallet.sign(psbt.clone(), Some(sign_options)).unwrap());
+        let psbt = psbt.0.lock().unwrap();
+        let witness = psbt.inputs[0].final_script_witness.as_ref().unwrap();
+        // The signature is the first witness element and ends with its sighash byte
+        let signature = witness.nth(0).unwrap();
+        assert_eq!(*signature.last().unwrap(), EcdsaSighashType::Single as u8);
+    }
+
+    #[test]
+    fn test_sign_message() {
+        let wallet = create_wallet();
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+
+        let signature = wallet
+            .sign_message("Hello World".to_string(), address)
+            .unwrap();
+        assert!(!signature.is_empty());
+        // the signature is the encoded witness of a p2wpkh spend: a signature and a public key
+        let witness: Witness = deserialize(&STANDARD.decode(&signature).unwrap()).unwrap();
+        assert_eq!(witness.len(), 2);
+        assert_eq!(witness.nth(1).unwrap().len(), 33);
+
+        let foreign_address = Arc::new(
+            Address::new(
+                "tb1qrnfslnrve9uncz9pzpvf83k3ukz22ljgees989".to_string(),
+                Network::Testnet,
+            )
+            .unwrap(),
+        );
+        assert!(matches!(
+            wallet.sign_message("Hello World".to_string(), foreign_address),
+            Err(MessageSignatureError::AddressNotInWallet)
+        ));
+    }
+
+    #[test]
+    fn test_sign_and_verify_message() {
+        let wallet = create_wallet();
+        let address = wallet.reveal_next_address(KeychainKind::External).address;
+
+        let signature = wallet
+            .sign_message("Hello World".to_string(), address.clone())
+            .unwrap();
+        assert!(address
+            .verify_message("Hello World".to_string(), signature.clone())
+            .unwrap());
+        assert!(!address
+            .verify_message("Hello World!".to_string(), signature)
+            .unwrap());
+    }
+
+    #[derive(Default)]
+    struct ProgressRecorder(Mutex<Vec<SyncProgress>>);
+    impl SyncProgressInspector for ProgressRecorder {
+        fn inspect(&self, progress: SyncProgress) {
+            self.0.lock().unwrap().push(progress);
+        }
+    }
+
+    #[test]
+    fn test_sync_progress_inspector() {
+        let wallet = create_wallet();
+        wallet.reveal_addresses_to(KeychainKind::External, 2);
+        let recorder = Arc::new(ProgressRecorder::default());
+
+        let sync_request = wallet
+            .start_sync_with_revealed_spks()
+            .inspect_progress(recorder.clone())
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut sync_request = sync_request.0.lock().unwrap().take().unwrap();
+        while sync_request.next_spk().is_some() {}
+
+        let recorded = recorder.0.lock().unwrap();
+        assert_eq!(recorded.len(), 3);
+        for (i, progress) in recorded.iter().enumerate() {
+            assert_eq!(progress.spks_consumed, i as u64 + 1);
+            assert_eq!(progress.spks_remaining, 2 - i as u64);
+            assert_eq!(progress.txids_remaining, 0);
+        }
+    }
+
+    #[test]
+    fn test_balance_with_policy() {
+        let wallet = create_wallet();
+        fund_wallet(&wallet, 50_000);
+
+        let balance = wallet.balance();
+        assert_eq!(balance.untrusted_pending.to_sat(), 50_000);
+        assert_eq!(balance.trusted_spendable.to_sat(), 0);
+
+        let strict = wallet.balance_with_policy(false);
+        assert_eq!(strict.trusted_spendable.to_sat(), 0);
+        assert_eq!(strict.total.to_sat(), 50_000);
+
+        let lenient = wallet.balance_with_policy(true);
+        assert_eq!(lenient.trusted_spendable.to_sat(), 50_000);
+        assert_eq!(lenient.total.to_sat(), 50_000);
+
+        // only the spendable amount depends on the policy, the components are left untouched
+        for policy_balance in [strict, lenient] {
+            assert_eq!(policy_balance.untrusted_pending.to_sat(), 50_000);
+            assert_eq!(policy_balance.trusted_pending.to_sat(), 0);
+            assert_eq!(policy_balance.confirmed.to_sat(), 0);
+            assert_eq!(policy_balance.immature.to_sat(), 0);
+        }
+    }
+
+    #[test]
+    fn test_create_signed_transaction() {
+        let wallet = create_wallet();
+        fund_wallet(&wallet, 50_000);
+        let recipient = |amount: u64| ScriptAmount {
+            script: wallet
+                .peek_address(KeychainKind::External, 10)
+                .address
+                .script_pubkey(),
+            amount: Arc::new(bitcoin_ffi::Amount::from_sat(amount)),
+        };
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            2,
+        )));
+
+        let psbt = wallet
+            .create_signed_transaction(vec![recipient(10_000)], fee_rate.clone(), None)
+            .unwrap();
+        let bdk_psbt = psbt.0.lock().unwrap().clone();
+        assert!(bdk_psbt
+            .inputs
+            .iter()
+            .all(|input| input.final_script_witness.is_some()));
+        assert!(psbt.extract_tx().is_ok());
+
+        assert!(matches!(
+            wallet.create_signed_transaction(vec![recipient(100_000)], fee_rate, None),
+            Err(CreateTxError::InsufficientFunds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_create_signed_transaction_skips_frozen_utxos() {
+        let wallet = create_wallet();
+        let frozen = funding_outpoint(&fund_wallet(&wallet, 50_000));
+        wallet.freeze_utxo(frozen);
+        let recipient = ScriptAmount {
+            script: wallet
+                .peek_address(KeychainKind::External, 10)
+                .address
+                .script_pubkey(),
+            amount: Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        };
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            2,
+        )));
+
+        // the only utxo is frozen, so there is nothing left to spend
+        assert!(matches!(
+            wallet.create_signed_transaction(vec![recipient], fee_rate, None),
+            Err(CreateTxError::InsufficientFunds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_start_sync_with_spk_range() {
+        let wallet = create_wallet();
+
+        let sync_request = wallet
+            .start_sync_with_spk_range(500, 502)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut sync_request = sync_request.0.lock().unwrap().take().unwrap();
+        let mut spks = Vec::new();
+        while let Some(spk) = sync_request.next_spk() {
+            spks.push(spk);
+        }
+
+        let wallet_ref = &wallet;
+        let expected: Vec<_> = [KeychainKind::External, KeychainKind::Internal]
+            .into_iter()
+            .flat_map(|keychain| {
+                (500..=502).map(move |index| wallet_ref.peek_address(keychain, index))
+            })
+            .map(|address_info| address_info.address.script_pubkey().0.clone())
+            .collect();
+        assert_eq!(spks, expected);
+        // building the request does not reveal anything
+        assert_eq!(wallet.derivation_index(KeychainKind::External), None);
+        assert_eq!(wallet.derivation_index(KeychainKind::Internal), None);
+
+        // an update paying to index 501 reveals the external keychain up to it
+        let script_pubkey = wallet
+            .peek_address(KeychainKind::External, 501)
+            .address
+            .script_pubkey()
+            .0
+            .clone();
+        let mut tx_update = TxUpdate::default();
+        tx_update
+            .txs
+            .push(Arc::new(funding_tx(script_pubkey, 50_000, 0)));
+        let update = BdkUpdate {
+            last_active_indices: BTreeMap::new(),
+            tx_update,
+            chain: None,
+        };
+        wallet.apply_update(Arc::new(Update(update))).unwrap();
+        assert_eq!(wallet.derivation_index(KeychainKind::External), Some(501));
+        assert_eq!(wallet.derivation_index(KeychainKind::Internal), None);
+    }
+
+    #[test]
+    fn test_start_sync_with_invalid_spk_range() {
+        let wallet = create_wallet();
+
+        assert!(matches!(
+            wallet.start_sync_with_spk_range(10, 5),
+            Err(RequestBuilderError::InvalidSpkRange {
+                from_index: 10,
+                to_index: 5
             })
+        ));
+        assert!(matches!(
+            wallet.start_sync_with_spk_range(0, MAX_SPK_RANGE),
+            Err(RequestBuilderError::SpkRangeTooLarge { .. })
+        ));
+        assert!(wallet
+            .start_sync_with_spk_range(0, MAX_SPK_RANGE - 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_start_full_scan_for_keychain() {
+        let wallet = create_wallet();
+
+        let full_scan_request = wallet
+            .start_full_scan_for_keychain(KeychainKind::Internal)
+            .build()
+            .unwrap();
+        let mut full_scan_request = full_scan_request.0.lock().unwrap().take().unwrap();
+        assert_eq!(full_scan_request.keychains(), vec![KeychainKind::Internal]);
+
+        let spks: Vec<_> = full_scan_request
+            .iter_spks(KeychainKind::Internal)
+            .take(3)
+            .collect();
+        for (index, spk) in spks {
+            let address = wallet.peek_address(KeychainKind::Internal, index).address;
+            assert_eq!(spk, address.script_pubkey().0);
+        }
+    }
+
+    #[test]
+    fn test_cancel_tx() {
+        let wallet = Arc::new(create_wallet());
+        fund_wallet(&wallet, 50_000);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let tx_builder = TxBuilder::new().add_recipient(
+            &address.script_pubkey(),
+            Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+        );
+
+        let psbt = tx_builder.finish(&wallet).unwrap();
+        // building the transaction used up the first change address
+        assert_eq!(wallet.next_unused_address(KeychainKind::Internal).index, 1);
+
+        wallet.cancel_tx(&psbt.extract_tx().unwrap());
+        assert_eq!(wallet.next_unused_address(KeychainKind::Internal).index, 0);
+        // the inputs were never reserved, so they can still be spent
+        assert_eq!(wallet.list_unspent().len(), 1);
+        assert!(tx_builder.finish(&wallet).is_ok());
+    }
+
+    #[test]
+    fn test_insert_tx() {
+        // a wallet on the same descriptors hands the transaction over
+        let tx = Transaction::from(fund_wallet(&create_wallet(), 25_000));
+        let wallet = create_wallet();
+        wallet.reveal_next_address(KeychainKind::External);
+
+        assert!(wallet.insert_tx(&tx, 100));
+        assert_eq!(wallet.balance().untrusted_pending.to_sat(), 25_000);
+        // inserting it again does not change the wallet
+        assert!(!wallet.insert_tx(&tx, 200));
+        assert_eq!(wallet.balance().total.to_sat(), 25_000);
+    }
+
+    #[test]
+    fn test_list_output() {
+        let wallet = Arc::new(create_wallet());
+        let funding_tx = fund_wallet(&wallet, 50_000);
+        let spent = funding_outpoint(&funding_tx);
+        let address = wallet.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&wallet)
+            .unwrap();
+        wallet.sign(psbt.clone(), None).unwrap();
+        wallet.insert_tx(&psbt.extract_tx().unwrap(), 200);
+
+        // the funding output is gone from the utxos but is still listed as a spent output
+        assert!(wallet
+            .list_unspent()
+            .iter()
+            .all(|output| output.outpoint != spent));
+        let outputs = wallet.list_output();
+        let funding_output = outputs
+            .iter()
+            .find(|output| output.outpoint == spent)
+            .unwrap();
+        assert!(funding_output.is_spent);
+        assert!(outputs.iter().any(|output| !output.is_spent));
+    }
+
+    #[test]
+    fn test_combine_and_finalize_multisig() {
+        // Each cosigner of a 2-of-2 holds one private key and the other's public key
+        let keys = [TPRV, COSIGNER_TPRV];
+        let cosigner = |signer: usize| {
+            let key = |i: usize| {
+                if i == signer {
+                    keys[i].to_string()
+                } else {
+                    DescriptorSecretKey::from_string(keys[i].to_string())
+                        .unwrap()
+                        .as_public()
+                        .as_string()
+                }
+            };
+            let wallet = create_wallet_with(|keychain| {
+                format!(
+                    "wsh(multi(2,{0}/{2}/*,{1}/{2}/*))",
+                    key(0),
+                    key(1),
+                    keychain
+                )
+            });
+            fund_wallet(&wallet, 50_000);
+            Arc::new(wallet)
+        };
+        let (alice, bob) = (cosigner(0), cosigner(1));
+        let address = alice.peek_address(KeychainKind::External, 10).address;
+        let psbt = TxBuilder::new()
+            .add_recipient(
+                &address.script_pubkey(),
+                Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+            )
+            .finish(&alice)
+            .unwrap();
+        let bob_psbt = Arc::new(Psbt::from(psbt.0.lock().unwrap().clone()));
+
+        assert!(!alice.sign(psbt.clone(), None).unwrap());
+        assert!(!bob.sign(bob_psbt.clone(), None).unwrap());
+
+        // A single signature is not enough, and the partial signature is left in place
+        let result = psbt.finalize();
+        assert!(!result.could_finalize);
+        assert_eq!(
+            result.psbt.0.lock().unwrap().inputs[0].partial_sigs.len(),
+            1
+        );
+
+        let result = psbt.combine(bob_psbt).unwrap().finalize();
+        assert!(result.could_finalize);
+        assert!(result.errors.is_none());
+        assert!(result.psbt.extract_tx().is_ok());
+    }
+
+    #[test]
+    fn test_frozen_utxos_are_persisted() {
+        let descriptor = |keychain: u32| format!("wpkh({}/84h/1h/0h/{}/*)", TPRV, keychain);
+        let connection = Arc::new(Connection::new_in_memory().unwrap());
+        let wallet = create_wallet_with_connection(descriptor, connection.clone());
+        let funding_tx = fund_wallet(&wallet, 50_000);
+        let outpoint = funding_outpoint(&funding_tx);
+        wallet.freeze_utxo(outpoint);
+        assert!(wallet.persist(connection.clone()).unwrap());
+        // nothing is left to persist
+        assert!(!wallet.persist(connection.clone()).unwrap());
+
+        let loaded = load_wallet(descriptor, connection.clone());
+        assert_eq!(loaded.list_frozen_utxos(), vec![outpoint]);
+
+        loaded.unfreeze_utxo(outpoint);
+        assert!(loaded.persist(connection.clone()).unwrap());
+        let loaded = load_wallet(descriptor, connection);
+        assert!(loaded.list_frozen_utxos().is_empty());
+    }
+
+    #[test]
+    fn test_sign_taproot() {
+        let key = TPRV;
+        // The internal key of the script path wallet belongs to someone else
+        let internal_key = TPUBS[0];
+        let sign = |descriptor: &dyn Fn(u32) -> String, sign_with_tap_internal_key: bool| {
+            let wallet = Arc::new(create_wallet_with(descriptor));
+            fund_wallet(&wallet, 50_000);
+            let address = wallet.peek_address(KeychainKind::External, 10).address;
+            let psbt = TxBuilder::new()
+                .add_recipient(
+                    &address.script_pubkey(),
+                    Arc::new(bitcoin_ffi::Amount::from_sat(10_000)),
+                )
+                .finish(&wallet)
+                .unwrap();
+            let sign_options = SignOptions {
+                trust_witness_utxo: false,
+                assume_height: None,
+                allow_all_sighashes: false,
+                try_finalize: true,
+                sign_with_tap_internal_key,
+                allow_grinding: true,
+            };
+            let finalized = wallet.sign(psbt.clone(), Some(sign_options)).unwrap();
+            let witness_len = psbt
+                .0
+                .lock()
+                .unwrap()
+                .inputs
+                .first()
+                .and_then(|input| input.final_script_witness.as_ref())
+                .map(|witness| witness.len());
+            (finalized, witness_len)
+        };
+
+        // A key path spend is a single schnorr signature
+        let key_path = |keychain: u32| format!("tr({}/86'/1'/0'/{}/*)", key, keychain);
+        assert_eq!(sign(&key_path, true), (true, Some(1)));
+        // which is only produced when signing with the internal key is allowed
+        assert_eq!(sign(&key_path, false), (false, None));
+
+        // A script path spend reveals the signature, the leaf script and the control block
+        let script_path =
+            |keychain: u32| format!("tr({0}/{2}/*,pk({1}/{2}/*))", internal_key, key, keychain);
+        assert_eq!(sign(&script_path, true), (true, Some(3)));
+    }
+
+    #[test]
+    fn test_build_consolidation_tx() {
+        let wallet = create_wallet();
+        for amount in [30_000, 10_000, 20_000, 40_000] {
+            fund_wallet(&wallet, amount);
+        }
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            2,
+        )));
+        let amount_of = |outpoint: &OutPoint| {
+            wallet
+                .list_unspent()
+                .into_iter()
+                .find(|utxo| &utxo.outpoint == outpoint)
+                .unwrap()
+                .txout
+                .value
+        };
+
+        let psbt = wallet
+            .build_consolidation_tx(fee_rate.clone(), None)
+            .unwrap();
+        let tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+        assert_eq!(tx.input.len(), 4);
+        assert_eq!(tx.output.len(), 1);
+        let change_script = wallet
+            .peek_address(KeychainKind::Internal, 0)
+            .address
+            .script_pubkey();
+        assert_eq!(tx.output[0].script_pubkey, change_script.0);
+
+        // only the smallest utxos are merged
+        let psbt = wallet
+            .build_consolidation_tx(fee_rate.clone(), Some(2))
+            .unwrap();
+        let tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+        assert_eq!(tx.output.len(), 1);
+        let mut amounts: Vec<u64> = tx
+            .input
+            .iter()
+            .map(|input| amount_of(&input.previous_output))
+            .collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![10_000, 20_000]);
+
+        for max_inputs in [0, 1] {
+            assert!(matches!(
+                wallet.build_consolidation_tx(fee_rate.clone(), Some(max_inputs)),
+                Err(CreateTxError::InvalidMaxInputs { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_build_consolidation_tx_skips_uneconomic_utxos() {
+        let wallet = create_wallet();
+        let fee_rate = Arc::new(bitcoin_ffi::FeeRate(BdkFeeRate::from_sat_per_vb_unchecked(
+            2,
+        )));
+        // spending a p2wpkh input costs more than 100 sat at 2 sat/vB
+        let dust = funding_outpoint(&fund_wallet(&wallet, 100));
+        fund_wallet(&wallet, 30_000);
+        assert!(matches!(
+            wallet.build_consolidation_tx(fee_rate.clone(), None),
+            Err(CreateTxError::NotEnoughUtxosToConsolidate { available: 1 })
+        ));
+
+        fund_wallet(&wallet, 20_000);
+        let psbt = wallet.build_consolidation_tx(fee_rate, None).unwrap();
+        let tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+        assert_eq!(tx.input.len(), 2);
+        assert!(tx.input.iter().all(|input| input.previous_output != dust));
     }
 }
@@ -0,0 +1,57 @@
+package org.bitcoindevkit
+
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertNotNull
+import kotlin.test.assertNull
+import kotlin.test.assertTrue
+import org.rustbitcoin.bitcoin.FeeRate
+import org.rustbitcoin.bitcoin.Network
+
+private const val SIGNET_ESPLORA_URL = "http://signet.bitcoindevkit.net"
+
+class LiveEsploraClientTest {
+    private val descriptor: Descriptor = Descriptor(
+        "wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/0h/0/*)",
+        Network.SIGNET
+    )
+    private val changeDescriptor: Descriptor = Descriptor(
+        "wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/0h/1/*)",
+        Network.SIGNET
+    )
+
+    @Test
+    fun testEstimateFee() {
+        val esploraClient: EsploraClient = EsploraClient(SIGNET_ESPLORA_URL)
+        val feeRate: FeeRate = esploraClient.estimateFee(6uL)
+        println("Fee rate for a 6 block target: ${feeRate.toSatPerVbCeil()} sat/vB")
+
+        assertTrue(feeRate.toSatPerVbCeil() > 0uL, "Fee rate estimate should be positive")
+    }
+
+    @Test
+    fun testGetTx() {
+        val wallet: Wallet = Wallet(descriptor, changeDescriptor, Network.SIGNET, Connection.newInMemory())
+        val esploraClient: EsploraClient = EsploraClient(SIGNET_ESPLORA_URL)
+        val fullScanRequest: FullScanRequest = wallet.startFullScan().build()
+        val update = esploraClient.fullScan(fullScanRequest, 10uL, 1uL)
+        wallet.applyUpdate(update)
+
+        assert(wallet.transactions().isNotEmpty()) {
+            "Wallet must have at least one transaction! Please send funds to ${wallet.revealNextAddress(KeychainKind.EXTERNAL).address} and try again."
+        }
+
+        val txid: String = wallet.transactions().first().transaction.computeTxid()
+        val tx = esploraClient.getTx(txid)
+        assertNotNull(tx, "A known txid should return its transaction")
+        assertEquals(txid, tx.computeTxid())
+
+        val unknownTxid = "0000000000000000000000000000000000000000000000000000000000000000"
+        assertNull(esploraClient.getTx(unknownTxid), "An unknown txid should return null")
+
+        assertFailsWith<EsploraException.Parsing> {
+            esploraClient.getTx("not-a-txid")
+        }
+    }
+}
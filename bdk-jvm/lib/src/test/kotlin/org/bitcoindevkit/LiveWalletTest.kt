@@ -2,6 +2,7 @@ package org.bitcoindevkit
 
 import kotlin.test.AfterTest
 import kotlin.test.Test
+import kotlin.test.assertEquals
 import kotlin.test.assertTrue
 import java.io.File
 import org.rustbitcoin.bitcoin.Amount
@@ -96,6 +97,7 @@ class LiveWalletTest {
         val feeRate: FeeRate = wallet.calculateFeeRate(tx)
         println("Tx fee rate is: ${feeRate.toSatPerVbCeil()} sat/vB")
 
-        esploraClient.broadcast(tx)
+        val txid: String = esploraClient.broadcast(tx)
+        assertEquals(tx.computeTxid(), txid, "Broadcast txid should match the transaction txid")
     }
 }
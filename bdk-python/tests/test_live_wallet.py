@@ -104,7 +104,8 @@ class LiveWalletTest(unittest.TestCase):
         fee_rate = wallet.calculate_fee_rate(tx)
         print(f"Transaction Fee Rate: {fee_rate.to_sat_per_vb_ceil()} sat/vB")
         
-        esplora_client.broadcast(tx)
+        txid = esplora_client.broadcast(tx)
+        self.assertEqual(txid, tx.compute_txid(), "Broadcast txid should match the transaction txid")
     
     
 if __name__ == '__main__':
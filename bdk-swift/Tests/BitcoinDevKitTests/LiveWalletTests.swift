@@ -114,6 +114,7 @@ final class LiveWalletTests: XCTestCase {
         let feeRate: FeeRate = try wallet.calculateFeeRate(tx: tx)
         print("Transaction Fee Rate: \(feeRate.toSatPerVbCeil()) sat/vB")
 
-        try esploraClient.broadcast(transaction: tx)
+        let txid: String = try esploraClient.broadcast(transaction: tx)
+        XCTAssertEqual(txid, tx.computeTxid(), "Broadcast txid should match the transaction txid")
     }
 }